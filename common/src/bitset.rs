@@ -0,0 +1,141 @@
+use std::iter::FromIterator;
+
+// Growable set of small non-negative integers, one bit per value
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct BitSet {
+    // Never ends in a zero word, so equal sets compare equal
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> BitSet {
+        BitSet { words: vec![] }
+    }
+
+    // Returns whether the value was not already present
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / 64, value % 64);
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+
+        let added = self.words[word] & (1 << bit) == 0;
+        self.words[word] |= 1 << bit;
+        added
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words
+            .get(value / 64)
+            .is_some_and(|word| word & (1 << (value % 64)) != 0)
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let (long, short) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut words = long.words.clone();
+        words
+            .iter_mut()
+            .zip(short.words.iter())
+            .for_each(|(a, b)| *a |= b);
+        BitSet { words }
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut words: Vec<u64> = self
+            .words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(a, b)| a & b)
+            .collect();
+
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        BitSet { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // Members in ascending order
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            words: &self.words,
+            index: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> BitSet {
+        let mut set = BitSet::new();
+        iter.into_iter().for_each(|value| {
+            set.insert(value);
+        });
+        set
+    }
+}
+
+pub struct Iter<'a> {
+    words: &'a [u64],
+    index: usize,
+    current: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    // Skips empty words, then takes the lowest set bit of the current one
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.index * 64 + bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BitSet;
+
+    #[test]
+    fn insert_and_contains() {
+        let mut set = BitSet::new();
+
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(1000));
+        assert!(set.contains(1000));
+        assert!(!set.contains(999));
+        assert!(!set.contains(100_000));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: BitSet = [1, 64, 200, 5000].into_iter().collect();
+        let b: BitSet = [1, 200, 300].into_iter().collect();
+
+        assert_eq!(a.intersection(&b), [1, 200].into_iter().collect());
+        assert_eq!(
+            a.union(&b).iter().collect::<Vec<usize>>(),
+            [1, 64, 200, 300, 5000]
+        );
+        assert!(a.intersection(&[2, 5001].into_iter().collect()).is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use std::cmp::{max, min};
+
+// Integer types an interval can be built from
+pub trait Discrete: Copy + Ord {
+    fn checked_next(self) -> Option<Self>;
+    fn checked_prev(self) -> Option<Self>;
+    // Number of steps from `from` up to `to`, which must not be smaller
+    fn distance(from: Self, to: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(impl Discrete for $t {
+            fn checked_next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn checked_prev(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn distance(from: Self, to: Self) -> u64 {
+                (to as i128 - from as i128) as u64
+            }
+        })*
+    };
+}
+
+impl_discrete!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+// Non-empty run of consecutive values. Both ends are stored inclusive, so an
+// interval can reach the largest value of its type.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Interval<T: Discrete> {
+    start: T,
+    end: T,
+}
+
+impl<T: Discrete> Interval<T> {
+    // Everything from `start` up to and including `end`, or None if that is
+    // empty
+    pub fn inclusive(start: T, end: T) -> Option<Interval<T>> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    // Everything from `start` up to but excluding `end`, or None if that is
+    // empty
+    pub fn exclusive(start: T, end: T) -> Option<Interval<T>> {
+        if start >= end {
+            return None;
+        }
+        Interval::inclusive(start, end.checked_prev()?)
+    }
+
+    pub fn single(value: T) -> Interval<T> {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    // Last value inside the interval
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // Number of values, saturating for the full range of a 64-bit type. There
+    // is no is_empty as intervals never are.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        T::distance(self.start, self.end).saturating_add(1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    // Whether every value of `other` is also in this interval
+    pub fn covers(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    // Whether the two intervals touch without sharing a value
+    pub fn is_adjacent(&self, other: &Interval<T>) -> bool {
+        self.end.checked_next() == Some(other.start) || other.end.checked_next() == Some(self.start)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::inclusive(max(self.start, other.start), min(self.end, other.end))
+    }
+
+    // The single interval covering both, if they overlap or touch
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if !self.overlaps(other) && !self.is_adjacent(other) {
+            return None;
+        }
+
+        Some(Interval {
+            start: min(self.start, other.start),
+            end: max(self.end, other.end),
+        })
+    }
+
+    // The values of this interval that are not in `other`, as the parts before
+    // and after it
+    pub fn difference(&self, other: &Interval<T>) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        if !self.overlaps(other) {
+            return if self.end < other.start {
+                (Some(*self), None)
+            } else {
+                (None, Some(*self))
+            };
+        }
+
+        let before = other
+            .start
+            .checked_prev()
+            .and_then(|end| Interval::inclusive(self.start, end));
+        let after = other
+            .end
+            .checked_next()
+            .and_then(|start| Interval::inclusive(start, self.end));
+
+        (before, after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::Interval;
+
+    const LOW: i32 = -3;
+    const HIGH: i32 = 4;
+
+    // Every interval within LOW..=HIGH
+    fn all_intervals() -> Vec<Interval<i32>> {
+        (LOW..=HIGH)
+            .flat_map(|start| {
+                (start..=HIGH).map(move |end| Interval::inclusive(start, end).unwrap())
+            })
+            .collect()
+    }
+
+    fn model(interval: &Interval<i32>) -> BTreeSet<i32> {
+        (interval.start()..=interval.end()).collect()
+    }
+
+    fn model_of(interval: Option<Interval<i32>>) -> BTreeSet<i32> {
+        interval.map(|i| model(&i)).unwrap_or_default()
+    }
+
+    // Whether the set is one run of consecutive values
+    fn is_contiguous(set: &BTreeSet<i32>) -> bool {
+        match (set.first(), set.last()) {
+            (Some(first), Some(last)) => (last - first + 1) as usize == set.len(),
+            _ => true,
+        }
+    }
+
+    #[test]
+    fn construction() {
+        assert_eq!(Interval::inclusive(2, 4), Interval::exclusive(2, 5));
+        assert_eq!(Interval::inclusive(4, 4), Some(Interval::single(4)));
+        assert_eq!(Interval::inclusive(5, 4), None);
+        assert_eq!(Interval::exclusive(4, 4), None);
+        assert_eq!(Interval::exclusive(0u8, 0), None);
+
+        let full = Interval::inclusive(u8::MIN, u8::MAX).unwrap();
+        assert_eq!(full.len(), 256);
+        assert_eq!(
+            Interval::inclusive(i64::MIN, i64::MAX).unwrap().len(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn single_interval_matches_model() {
+        for interval in all_intervals() {
+            let set = model(&interval);
+
+            assert_eq!(interval.len(), set.len() as u64);
+            for value in LOW - 1..=HIGH + 1 {
+                assert_eq!(interval.contains(value), set.contains(&value));
+            }
+        }
+    }
+
+    #[test]
+    fn pairs_match_model() {
+        for a in all_intervals() {
+            for b in all_intervals() {
+                let (sa, sb) = (model(&a), model(&b));
+                let both: BTreeSet<i32> = sa.intersection(&sb).copied().collect();
+                let either: BTreeSet<i32> = sa.union(&sb).copied().collect();
+                let only_a: BTreeSet<i32> = sa.difference(&sb).copied().collect();
+
+                assert_eq!(model_of(a.intersection(&b)), both, "{a:?} & {b:?}");
+                assert_eq!(a.overlaps(&b), !both.is_empty());
+                assert_eq!(a.covers(&b), sb.is_subset(&sa));
+
+                assert_eq!(a.union(&b).is_some(), is_contiguous(&either));
+                if let Some(union) = a.union(&b) {
+                    assert_eq!(model(&union), either, "{a:?} | {b:?}");
+                }
+
+                let adjacent = both.is_empty() && is_contiguous(&either);
+                assert_eq!(a.is_adjacent(&b), adjacent, "{a:?} ~ {b:?}");
+
+                let (before, after) = a.difference(&b);
+                let before = model_of(before);
+                let after = model_of(after);
+                assert!(before.iter().all(|v| after.iter().all(|w| v < w)));
+                assert_eq!(
+                    before.union(&after).copied().collect::<BTreeSet<i32>>(),
+                    only_a,
+                    "{a:?} - {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn edges_of_the_type() {
+        let low = Interval::inclusive(0u8, 10).unwrap();
+        let high = Interval::inclusive(250u8, 255).unwrap();
+        let all = Interval::inclusive(0u8, 255).unwrap();
+
+        assert_eq!(all.difference(&low), (None, Interval::inclusive(11, 255)));
+        assert_eq!(all.difference(&high), (Interval::inclusive(0, 249), None));
+        assert!(!high.is_adjacent(&low));
+        assert_eq!(
+            high.union(&Interval::single(249)),
+            Interval::inclusive(249, 255)
+        );
+    }
+}
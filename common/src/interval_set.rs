@@ -0,0 +1,158 @@
+use std::cmp::max;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+use crate::{Discrete, Interval};
+
+// Union of intervals, kept as disjoint intervals that do not touch each other
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct IntervalSet<T: Discrete> {
+    // Inclusive end of every interval, keyed by its start
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    // Merges the interval with every stored one it overlaps or touches
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut start = interval.start();
+        let mut end = interval.end();
+
+        if let Some((&s, &e)) = self.ranges.range(..=start).next_back() {
+            if e.checked_next().is_none_or(|next| next >= start) {
+                start = s;
+                end = max(end, e);
+                self.ranges.remove(&s);
+            }
+        }
+
+        while let Some((&s, &e)) = self.ranges.range(start..).next() {
+            if end.checked_next().is_some_and(|next| s > next) {
+                break;
+            }
+            end = max(end, e);
+            self.ranges.remove(&s);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, end)| value <= *end)
+    }
+
+    // Total number of values, saturating like Interval::len
+    pub fn len(&self) -> u64 {
+        self.iter()
+            .fold(0u64, |acc, interval| acc.saturating_add(interval.len()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // The disjoint intervals in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.ranges
+            .iter()
+            .filter_map(|(start, end)| Interval::inclusive(*start, *end))
+    }
+
+    // The values missing between the first and the last interval
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.iter().zip(self.iter().skip(1)).filter_map(|(a, b)| {
+            Interval::inclusive(a.end().checked_next()?, b.start().checked_prev()?)
+        })
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        iter.into_iter().for_each(|interval| set.insert(interval));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::{Interval, IntervalSet, Rng};
+
+    #[test]
+    fn merges_overlapping_and_touching() {
+        let set: IntervalSet<i32> = [(5, 7), (1, 2), (3, 3), (10, 12), (6, 9), (20, 20)]
+            .into_iter()
+            .filter_map(|(start, end)| Interval::inclusive(start, end))
+            .collect();
+
+        assert_eq!(
+            set.iter().collect::<Vec<Interval<i32>>>(),
+            [
+                Interval::inclusive(1, 3).unwrap(),
+                Interval::inclusive(5, 12).unwrap(),
+                Interval::single(20),
+            ]
+        );
+        assert_eq!(set.len(), 3 + 8 + 1);
+        assert_eq!(
+            set.gaps().collect::<Vec<Interval<i32>>>(),
+            [Interval::single(4), Interval::inclusive(13, 19).unwrap()]
+        );
+        assert!(set.contains(8) && !set.contains(13));
+    }
+
+    #[test]
+    fn matches_model() {
+        let mut rng = Rng::new(7);
+        let mut next = |below: usize| rng.below(below);
+
+        for _ in 0..200 {
+            let mut set = IntervalSet::new();
+            let mut model = BTreeSet::new();
+
+            for _ in 0..next(8) {
+                let start = next(40) as u8;
+                let end = start.saturating_add(next(6) as u8);
+                set.insert(Interval::inclusive(start, end).unwrap());
+                model.extend(start..=end);
+            }
+
+            let values: BTreeSet<u8> = set.iter().flat_map(|i| i.start()..=i.end()).collect();
+            assert_eq!(values, model);
+            assert_eq!(set.len(), model.len() as u64);
+
+            let intervals: Vec<Interval<u8>> = set.iter().collect();
+            assert!(intervals
+                .windows(2)
+                .all(|w| !w[0].overlaps(&w[1]) && !w[0].is_adjacent(&w[1])));
+
+            let gaps: BTreeSet<u8> = set.gaps().flat_map(|i| i.start()..=i.end()).collect();
+            if let (Some(first), Some(last)) = (model.first(), model.last()) {
+                let missing: BTreeSet<u8> =
+                    (*first..=*last).filter(|v| !model.contains(v)).collect();
+                assert_eq!(gaps, missing);
+            }
+        }
+    }
+
+    #[test]
+    fn edges_of_the_type() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::inclusive(250u8, 255).unwrap());
+        set.insert(Interval::inclusive(0u8, 3).unwrap());
+        set.insert(Interval::inclusive(4u8, 249).unwrap());
+
+        assert_eq!(set.iter().count(), 1);
+        assert_eq!(set.len(), 256);
+    }
+}
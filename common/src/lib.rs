@@ -1,4 +1,14 @@
-use std::{env, fs, process::exit};
+mod bitset;
+mod interval;
+mod interval_set;
+mod rng;
+
+pub use bitset::BitSet;
+pub use interval::{Discrete, Interval};
+pub use interval_set::IntervalSet;
+pub use rng::Rng;
+
+use std::{env, fs, process::exit, str::FromStr};
 
 pub fn read_input_file_from_args(arg_num: usize) -> Result<String, String> {
 
@@ -27,3 +37,30 @@ pub fn get_file_content_or_exit() -> String{
 
     input.unwrap()
 }
+
+pub fn has_flag(flag: &str) -> bool {
+    env::args().skip(1).any(|arg| arg == flag)
+}
+
+pub fn get_flag_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+    let pos = args.iter().position(|arg| arg == flag)?;
+
+    args.get(pos + 1).cloned()
+}
+
+pub fn parse_flag_value_or_exit<T: FromStr>(flag: &str) -> Option<T> {
+    let value = get_flag_value(flag)?;
+
+    match value.parse::<T>() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            println!("Invalid value for {flag}: {value}");
+            exit(1);
+        }
+    }
+}
+
+pub fn get_flag_value_or_exit<T: FromStr>(flag: &str, default: T) -> T {
+    parse_flag_value_or_exit(flag).unwrap_or(default)
+}
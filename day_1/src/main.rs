@@ -1,4 +1,87 @@
-use aoc::get_file_content_or_exit;
+use std::io::IsTerminal;
+
+use aoc::{get_file_content_or_exit, get_flag_value_or_exit, has_flag};
+
+const MAX_BAR_WIDTH: usize = 60;
+
+struct Bucket {
+    start: i32,
+    count: usize,
+    top: usize,
+}
+
+// Groups the elf totals into buckets of `width` calories, counting how many of
+// the `top_k` largest totals land in each bucket
+fn make_buckets(elves: &[i32], width: i32, top_k: usize) -> Vec<Bucket> {
+    let (Some(min), Some(max)) = (elves.iter().min(), elves.iter().max()) else {
+        return vec![];
+    };
+
+    let first = min.div_euclid(width);
+    let last = max.div_euclid(width);
+    let mut buckets: Vec<Bucket> = (first..=last)
+        .map(|i| Bucket {
+            start: i * width,
+            count: 0,
+            top: 0,
+        })
+        .collect();
+
+    let mut sorted = elves.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    sorted.iter().enumerate().for_each(|(rank, total)| {
+        let bucket = &mut buckets[(total.div_euclid(width) - first) as usize];
+        bucket.count += 1;
+        if rank < top_k {
+            bucket.top += 1;
+        }
+    });
+
+    buckets
+}
+
+// Buckets holding a top elf are highlighted with ANSI colours when `colour`
+// is set
+fn draw_histogram(elves: &[i32], width: i32, top_k: usize, colour: bool) {
+    let buckets = make_buckets(elves, width, top_k);
+    let max_count = buckets.iter().map(|b| b.count).max().unwrap_or(0);
+    let label_width = buckets
+        .iter()
+        .map(|b| (b.start + width - 1).to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    for bucket in buckets {
+        // Scale down to fit the terminal, but never hide a non-empty bucket
+        let mut bar_len = bucket.count * MAX_BAR_WIDTH / max_count.max(MAX_BAR_WIDTH);
+        if bucket.count > 0 {
+            bar_len = bar_len.max(1);
+        }
+
+        let range = format!(
+            "{:>w$}-{:<w$}",
+            bucket.start,
+            bucket.start + width - 1,
+            w = label_width
+        );
+
+        let bar = "#".repeat(bar_len);
+        if bucket.top > 0 {
+            let bar = if colour {
+                format!("\x1b[1;33m{bar}\x1b[0m")
+            } else {
+                bar
+            };
+            println!(
+                "{range} | {bar} {} ({} in top {top_k})",
+                bucket.count, bucket.top
+            );
+        } else {
+            println!("{range} | {bar} {}", bucket.count);
+        }
+    }
+}
 
 fn main() {
     let data = get_file_content_or_exit();
@@ -22,4 +105,47 @@ fn main() {
     // Part 2
     elves.sort();
     println!("Sum of top 3: {}", elves.iter().rev().take(3).sum::<i32>());
+
+    if has_flag("--histogram") {
+        let width = get_flag_value_or_exit("--bucket-width", 5000);
+        let top_k = get_flag_value_or_exit("--top", 3);
+        let colour = !has_flag("--no-colour") && std::io::stdout().is_terminal();
+
+        if width <= 0 {
+            println!("Bucket width must be positive");
+            return;
+        }
+
+        println!();
+        draw_histogram(&elves, width, top_k, colour);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::make_buckets;
+
+    #[test]
+    fn buckets_cover_all_elves() {
+        let elves = [6000, 4000, 11000, 24000, 10000];
+        let buckets = make_buckets(&elves, 5000, 3);
+
+        assert_eq!(buckets.len(), 5);
+        assert_eq!(buckets.iter().map(|b| b.count).sum::<usize>(), 5);
+        assert_eq!(
+            buckets.iter().map(|b| b.count).collect::<Vec<usize>>(),
+            [1, 1, 2, 0, 1]
+        );
+    }
+
+    #[test]
+    fn buckets_mark_top_elves() {
+        let elves = [6000, 4000, 11000, 24000, 10000];
+        let buckets = make_buckets(&elves, 5000, 3);
+
+        assert_eq!(
+            buckets.iter().map(|b| b.top).collect::<Vec<usize>>(),
+            [0, 0, 2, 0, 1]
+        );
+    }
 }
@@ -0,0 +1,287 @@
+// Alternative interpretations of the second column of the strategy guide.
+// Part 1 reads it as our shape and part 2 as the required result, but nothing
+// in the guide says which symbol means what.
+
+use crate::rules::{Rules, Shape};
+use crate::{provoke_result, score_round, MatchResult, Round};
+
+const RESULT_SYMBOLS: [char; 3] = ['X', 'Y', 'Z'];
+const RESULTS: [MatchResult; 3] = [MatchResult::Lose, MatchResult::Draw, MatchResult::Win];
+
+pub enum Mapping {
+    Shapes(Vec<(char, Shape)>),
+    Results(Vec<(char, MatchResult)>),
+}
+
+pub struct Encoding {
+    pub source: String,
+    pub mapping: Mapping,
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut perms = vec![];
+    for perm in permutations(n - 1) {
+        for i in 0..=perm.len() {
+            let mut next = perm.clone();
+            next.insert(i, n - 1);
+            perms.push(next);
+        }
+    }
+
+    perms.sort();
+    perms
+}
+
+// Every way of assigning our shape symbols to shapes, and every way of
+// assigning X/Y/Z to results
+pub fn all_encodings(rules: &Rules) -> Vec<Encoding> {
+    let symbols: Vec<char> = rules.shapes.iter().map(|s| s.own).collect();
+    let mut encodings: Vec<Encoding> = permutations(symbols.len())
+        .iter()
+        .map(|perm| Encoding {
+            source: String::from("search"),
+            mapping: Mapping::Shapes(
+                symbols
+                    .iter()
+                    .zip(perm.iter())
+                    .map(|(c, i)| (*c, Shape(*i)))
+                    .collect(),
+            ),
+        })
+        .collect();
+
+    encodings.extend(permutations(RESULTS.len()).iter().map(|perm| {
+        Encoding {
+            source: String::from("search"),
+            mapping: Mapping::Results(
+                RESULT_SYMBOLS
+                    .iter()
+                    .zip(perm.iter())
+                    .map(|(c, i)| (*c, RESULTS[*i]))
+                    .collect(),
+            ),
+        }
+    }));
+
+    encodings
+}
+
+// Reads one encoding per line, e.g.
+//
+//     shapes X=Rock Y=Paper Z=Scissor
+//     results X=Lose Y=Draw Z=Win
+pub fn parse_mapping_file(rules: &Rules, content: &str) -> Result<Vec<Encoding>, String> {
+    let mut encodings = vec![];
+
+    for (i, line) in content.lines().enumerate() {
+        let line_num = i + 1;
+        let mut fields = line.split_whitespace();
+        let scheme = match fields.next() {
+            None => continue,
+            Some(comment) if comment.starts_with('#') => continue,
+            Some(scheme) => scheme,
+        };
+
+        let mut pairs: Vec<(char, &str)> = vec![];
+        for field in fields {
+            let (symbol, value) = field.split_once('=').ok_or(format!(
+                "line {line_num}: expected SYMBOL=VALUE, got '{field}'"
+            ))?;
+            let mut chars = symbol.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => pairs.push((c.to_ascii_uppercase(), value)),
+                _ => return Err(format!("line {line_num}: invalid symbol '{symbol}'")),
+            }
+        }
+
+        let mapping = match scheme {
+            "shapes" => {
+                let own: Vec<char> = rules.shapes.iter().map(|s| s.own).collect();
+                check_symbols(&pairs, &own, line_num)?;
+
+                Mapping::Shapes(
+                    pairs
+                        .iter()
+                        .map(|(c, name)| {
+                            rules
+                                .all_shapes()
+                                .find(|s| rules.name(*s) == *name)
+                                .map(|s| (*c, s))
+                                .ok_or(format!("line {line_num}: unknown shape '{name}'"))
+                        })
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "results" => {
+                check_symbols(&pairs, &RESULT_SYMBOLS, line_num)?;
+
+                Mapping::Results(
+                    pairs
+                        .iter()
+                        .map(|(c, name)| {
+                            RESULTS
+                                .iter()
+                                .find(|r| format!("{r:?}") == *name)
+                                .map(|r| (*c, *r))
+                                .ok_or(format!("line {line_num}: unknown result '{name}'"))
+                        })
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            _ => return Err(format!("line {line_num}: unknown scheme '{scheme}'")),
+        };
+
+        encodings.push(Encoding {
+            source: format!("line {line_num}"),
+            mapping,
+        });
+    }
+
+    Ok(encodings)
+}
+
+fn check_symbols<T>(pairs: &[(char, T)], expected: &[char], line_num: usize) -> Result<(), String> {
+    let missing: Vec<&char> = expected
+        .iter()
+        .filter(|c| !pairs.iter().any(|(p, _)| p == *c))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("line {line_num}: no mapping for {missing:?}"));
+    }
+
+    if let Some((c, _)) = pairs.iter().find(|(c, _)| !expected.contains(c)) {
+        return Err(format!("line {line_num}: unexpected symbol '{c}'"));
+    }
+
+    for (i, (c, _)) in pairs.iter().enumerate() {
+        if pairs[..i].iter().any(|(p, _)| p == c) {
+            return Err(format!("line {line_num}: symbol '{c}' is mapped twice"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn score_encoding(rules: &Rules, rounds: &[Round], mapping: &Mapping) -> i32 {
+    rounds
+        .iter()
+        .map(|round| match mapping {
+            Mapping::Shapes(pairs) => pairs
+                .iter()
+                .find(|(c, _)| *c == round.column)
+                .map_or(0, |(_, you)| score_round(rules, &round.opponent, you)),
+            Mapping::Results(pairs) => {
+                pairs
+                    .iter()
+                    .find(|(c, _)| *c == round.column)
+                    .map_or(0, |(_, result)| {
+                        let you = provoke_result(rules, &round.opponent, *result);
+                        score_round(rules, &round.opponent, &you)
+                    })
+            }
+        })
+        .sum()
+}
+
+fn describe(rules: &Rules, mapping: &Mapping) -> String {
+    match mapping {
+        Mapping::Shapes(pairs) => pairs
+            .iter()
+            .map(|(c, s)| format!("{c}={}", rules.name(*s)))
+            .collect::<Vec<String>>()
+            .join(" "),
+        Mapping::Results(pairs) => pairs
+            .iter()
+            .map(|(c, r)| format!("{c}={r:?}"))
+            .collect::<Vec<String>>()
+            .join(" "),
+    }
+}
+
+// The best and worst rows are highlighted with ANSI colours when `colour` is
+// set
+pub fn print_score_table(rules: &Rules, rounds: &[Round], encodings: &[Encoding], colour: bool) {
+    for (scheme, shapes) in [("shapes", true), ("results", false)] {
+        let rows: Vec<(&Encoding, String, i32)> = encodings
+            .iter()
+            .filter(|e| matches!(e.mapping, Mapping::Shapes(_)) == shapes)
+            .map(|e| {
+                (
+                    e,
+                    describe(rules, &e.mapping),
+                    score_encoding(rules, rounds, &e.mapping),
+                )
+            })
+            .collect();
+
+        let best = rows.iter().map(|(_, _, score)| *score).max();
+        let worst = rows.iter().map(|(_, _, score)| *score).min();
+        let width = rows.iter().map(|(_, d, _)| d.len()).max().unwrap_or(0);
+
+        println!("Interpreting the second column as {scheme}:");
+        for (encoding, description, score) in rows.iter() {
+            let line = format!("  {description:<width$}  {score:>8}  ({})", encoding.source);
+            let highlight = if Some(*score) == best {
+                Some(("best", 32))
+            } else if Some(*score) == worst {
+                Some(("worst", 31))
+            } else {
+                None
+            };
+
+            match highlight {
+                Some((label, code)) if colour => println!("\x1b[1;{code}m{line} {label}\x1b[0m"),
+                Some((label, _)) => println!("{line} {label}"),
+                None => println!("{line}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encodings::*;
+    use crate::parse_rounds;
+
+    #[test]
+    fn finds_every_encoding() {
+        let rules = Rules::classic();
+        let encodings = all_encodings(&rules);
+
+        assert_eq!(encodings.len(), 12);
+    }
+
+    #[test]
+    fn default_encodings_match_parts() {
+        let rules = Rules::classic();
+        let (rounds, _) = parse_rounds(&rules, "A Y\nB X\nC Z\n");
+        let encodings = parse_mapping_file(
+            &rules,
+            "shapes X=Rock Y=Paper Z=Scissor\n\nresults X=Lose Y=Draw Z=Win\n",
+        )
+        .unwrap();
+
+        assert_eq!(score_encoding(&rules, &rounds, &encodings[0].mapping), 15);
+        assert_eq!(score_encoding(&rules, &rounds, &encodings[1].mapping), 12);
+    }
+
+    #[test]
+    fn rejects_incomplete_mapping() {
+        let rules = Rules::classic();
+
+        assert!(parse_mapping_file(&rules, "shapes X=Rock Y=Paper").is_err());
+        assert!(parse_mapping_file(&rules, "results X=Lose Y=Draw Z=Tie").is_err());
+    }
+
+    #[test]
+    fn rejects_repeated_symbol() {
+        let rules = Rules::classic();
+
+        assert!(parse_mapping_file(&rules, "shapes X=Rock X=Paper Y=Paper Z=Scissor").is_err());
+        assert!(parse_mapping_file(&rules, "results X=Lose Y=Draw Z=Win z=Lose").is_err());
+    }
+}
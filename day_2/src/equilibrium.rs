@@ -0,0 +1,180 @@
+// Optimal mixed strategies for the round score, treating each round as a
+// zero-sum game where the opponent tries to minimise our score
+
+use crate::rules::{Rules, Shape};
+use crate::score_round;
+
+const EPSILON: f64 = 1e-9;
+
+pub struct Equilibrium {
+    // Probability of each of our shapes
+    pub ours: Vec<f64>,
+    // The opponent's minimising strategy
+    pub theirs: Vec<f64>,
+    // Expected score per round when both sides play optimally
+    pub value: f64,
+}
+
+// payoff[i][j] is our score when we play shape i against shape j
+pub fn payoff_matrix(rules: &Rules) -> Vec<Vec<f64>> {
+    rules
+        .all_shapes()
+        .map(|ours| {
+            rules
+                .all_shapes()
+                .map(|theirs| score_round(rules, &theirs, &ours) as f64)
+                .collect()
+        })
+        .collect()
+}
+
+// Solves the matrix game with the simplex method. The payoffs are shifted to
+// be positive, after which the opponent's problem
+//
+//     maximise sum(y)  subject to  payoff * y <= 1, y >= 0
+//
+// starts from a feasible basis. Its optimum is 1 / value, the opponent's
+// strategy is y scaled to sum to one, and ours is read off the dual values
+// of the slack columns.
+pub fn solve(payoff: &[Vec<f64>]) -> Equilibrium {
+    let rows = payoff.len();
+    let cols = payoff.first().map_or(0, |row| row.len());
+    let min = payoff
+        .iter()
+        .flatten()
+        .fold(f64::INFINITY, |acc, v| acc.min(*v));
+    let shift = 1.0 - min;
+
+    // Tableau rows are the constraints followed by the objective, columns are
+    // the y variables, the slack variables and finally the right hand side
+    let width = cols + rows + 1;
+    let mut tableau: Vec<Vec<f64>> = payoff
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![0.0; width];
+            row.iter()
+                .enumerate()
+                .for_each(|(j, v)| line[j] = v + shift);
+            line[cols + i] = 1.0;
+            line[width - 1] = 1.0;
+            line
+        })
+        .collect();
+
+    let mut objective = vec![0.0; width];
+    objective[..cols].iter_mut().for_each(|v| *v = -1.0);
+    tableau.push(objective);
+
+    let mut basis: Vec<usize> = (cols..cols + rows).collect();
+
+    // Bland's rule: lowest index entering and leaving variables, which
+    // guarantees termination on degenerate games
+    while let Some(enter) = (0..width - 1).find(|j| tableau[rows][*j] < -EPSILON) {
+        let leave = (0..rows)
+            .filter(|i| tableau[*i][enter] > EPSILON)
+            .min_by(|a, b| {
+                let ra = tableau[*a][width - 1] / tableau[*a][enter];
+                let rb = tableau[*b][width - 1] / tableau[*b][enter];
+                ra.partial_cmp(&rb).unwrap().then(basis[*a].cmp(&basis[*b]))
+            })
+            .expect("game LP is bounded");
+
+        let pivot = tableau[leave][enter];
+        tableau[leave].iter_mut().for_each(|v| *v /= pivot);
+        let pivot_row = tableau[leave].clone();
+
+        for (i, row) in tableau.iter_mut().enumerate() {
+            if i != leave && row[enter].abs() > EPSILON {
+                let factor = row[enter];
+                row.iter_mut()
+                    .zip(pivot_row.iter())
+                    .for_each(|(v, p)| *v -= factor * p);
+            }
+        }
+
+        basis[leave] = enter;
+    }
+
+    let total = tableau[rows][width - 1];
+    let mut theirs = vec![0.0; cols];
+    basis.iter().enumerate().for_each(|(i, var)| {
+        if *var < cols {
+            theirs[*var] = tableau[i][width - 1] / total;
+        }
+    });
+    let ours = (0..rows).map(|i| tableau[rows][cols + i] / total).collect();
+
+    Equilibrium {
+        ours,
+        theirs,
+        value: 1.0 / total - shift,
+    }
+}
+
+pub fn print_equilibrium(rules: &Rules) {
+    let equilibrium = solve(&payoff_matrix(rules));
+    let width = rules.shapes.iter().map(|s| s.name.len()).max().unwrap_or(0);
+
+    println!("Optimal mixed strategy against a score-minimising opponent:");
+    for (i, p) in equilibrium.ours.iter().enumerate() {
+        println!(
+            "  {:width$}  {:>6.2}%  (opponent {:>6.2}%)",
+            rules.name(Shape(i)),
+            p * 100.0,
+            equilibrium.theirs[i] * 100.0
+        );
+    }
+    println!("Game value: {:.4} points per round", equilibrium.value);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::equilibrium::*;
+
+    fn assert_equilibrium(payoff: &[Vec<f64>]) {
+        let eq = solve(payoff);
+
+        assert!((eq.ours.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        assert!((eq.theirs.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+
+        // Our strategy guarantees at least the value against every reply,
+        // theirs holds us to at most the value
+        for j in 0..payoff[0].len() {
+            let score: f64 = (0..payoff.len()).map(|i| eq.ours[i] * payoff[i][j]).sum();
+            assert!(score >= eq.value - 1e-6);
+        }
+        for row in payoff.iter() {
+            let score: f64 = row.iter().zip(eq.theirs.iter()).map(|(a, q)| a * q).sum();
+            assert!(score <= eq.value + 1e-6);
+        }
+    }
+
+    #[test]
+    fn classic_equilibrium() {
+        assert_equilibrium(&payoff_matrix(&Rules::classic()));
+    }
+
+    #[test]
+    fn five_shape_equilibrium() {
+        let rules = Rules::parse(include_str!("../rules/rpsls.txt")).unwrap();
+
+        assert_equilibrium(&payoff_matrix(&rules));
+    }
+
+    #[test]
+    fn symmetric_game_value() {
+        // Plain rock-paper-scissors without shape scores is worth nothing
+        let payoff = vec![
+            vec![0.0, -1.0, 1.0],
+            vec![1.0, 0.0, -1.0],
+            vec![-1.0, 1.0, 0.0],
+        ];
+        let eq = solve(&payoff);
+
+        assert!(eq.value.abs() < 1e-9);
+        eq.ours
+            .iter()
+            .for_each(|p| assert!((p - 1.0 / 3.0).abs() < 1e-9));
+    }
+}
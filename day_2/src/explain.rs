@@ -0,0 +1,145 @@
+// Round by round breakdown of both interpretations of the strategy guide
+
+use crate::rules::{Rules, Shape};
+use crate::{
+    check_result, get_result_value, get_shape_value, parse_input, parse_required_result,
+    provoke_result, MatchResult, Round,
+};
+
+struct Explained {
+    line: usize,
+    opponent: Shape,
+    you: Shape,
+    result: MatchResult,
+}
+
+fn explain_part(
+    rules: &Rules,
+    rounds: &[Round],
+    choose: impl Fn(&Round) -> Option<Shape>,
+) -> Vec<Explained> {
+    rounds
+        .iter()
+        .filter_map(|round| {
+            let you = choose(round)?;
+            Some(Explained {
+                line: round.line,
+                opponent: round.opponent,
+                you,
+                result: check_result(rules, &round.opponent, &you),
+            })
+        })
+        .collect()
+}
+
+// Wins, draws and losses for each of our shapes
+fn count_outcomes(rules: &Rules, explained: &[Explained]) -> Vec<[usize; 3]> {
+    let mut counts = vec![[0; 3]; rules.shapes.len()];
+
+    for round in explained.iter() {
+        let column = match round.result {
+            MatchResult::Win => 0,
+            MatchResult::Draw => 1,
+            MatchResult::Lose => 2,
+        };
+        counts[round.you.0][column] += 1;
+    }
+
+    counts
+}
+
+fn print_part(rules: &Rules, title: &str, explained: &[Explained]) {
+    let width = rules
+        .shapes
+        .iter()
+        .map(|s| s.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    println!("{title}");
+    println!(
+        "{:>6}  {:width$}  {:width$}  {:7}  {:>5}  {:>7}  {:>8}",
+        "Line", "Opponent", "You", "Outcome", "Shape", "Outcome", "Total"
+    );
+
+    let mut total = 0;
+    for round in explained.iter() {
+        let shape_points = get_shape_value(rules, &round.you);
+        let result_points = get_result_value(&round.result);
+        total += shape_points + result_points;
+
+        println!(
+            "{:>6}  {:width$}  {:width$}  {:7}  {:>5}  {:>7}  {:>8}",
+            round.line,
+            rules.name(round.opponent),
+            rules.name(round.you),
+            format!("{:?}", round.result),
+            shape_points,
+            result_points,
+            total
+        );
+    }
+
+    let counts = count_outcomes(rules, explained);
+    println!();
+    println!(
+        "{:width$}  {:>6}  {:>6}  {:>6}",
+        "", "Wins", "Draws", "Losses"
+    );
+    for shape in rules.all_shapes() {
+        let [wins, draws, losses] = counts[shape.0];
+        println!(
+            "{:width$}  {wins:>6}  {draws:>6}  {losses:>6}",
+            rules.name(shape)
+        );
+    }
+    println!("Total: {total}");
+}
+
+fn explain_guide(rules: &Rules, rounds: &[Round]) -> Vec<Explained> {
+    explain_part(rules, rounds, |round| parse_input(rules, &round.column))
+}
+
+fn explain_provoked(rules: &Rules, rounds: &[Round]) -> Vec<Explained> {
+    explain_part(rules, rounds, |round| {
+        let result = parse_required_result(&round.column)?;
+        Some(provoke_result(rules, &round.opponent, result))
+    })
+}
+
+pub fn print_explanation(rules: &Rules, rounds: &[Round]) {
+    let guide = explain_guide(rules, rounds);
+    let provoked = explain_provoked(rules, rounds);
+
+    print_part(rules, "Part 1: second column is our shape", &guide);
+    println!();
+    print_part(
+        rules,
+        "Part 2: second column is the required result",
+        &provoked,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::explain::*;
+    use crate::parse_rounds;
+
+    #[test]
+    fn counts_example_outcomes() {
+        let rules = Rules::classic();
+        let (rounds, _) = parse_rounds(&rules, "A Y\nB X\nC Z");
+
+        // Paper beats rock, rock loses to paper, scissors draw
+        assert_eq!(
+            count_outcomes(&rules, &explain_guide(&rules, &rounds)),
+            [[0, 0, 1], [1, 0, 0], [0, 1, 0]]
+        );
+        // Rock every time: a draw, a loss and a win
+        assert_eq!(
+            count_outcomes(&rules, &explain_provoked(&rules, &rounds)),
+            [[1, 1, 1], [0, 0, 0], [0, 0, 0]]
+        );
+    }
+}
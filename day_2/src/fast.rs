@@ -0,0 +1,181 @@
+// Table-driven scoring straight from the raw bytes. Only lines that are
+// exactly "A X" under the classic rules are understood, anything else is
+// counted so the caller can fall back to the regular path.
+
+use std::time::Instant;
+
+use aoc::Rng;
+
+use crate::rules::Rules;
+use crate::{
+    parse_input, parse_required_result, parse_rounds, provoke_result, score_guide, score_provoked,
+    score_round,
+};
+
+// Both parts' round scores, indexed by (opponent - 'A') * 3 + (column - 'X')
+pub struct ScoreTable {
+    guide: [i32; 9],
+    provoked: [i32; 9],
+}
+
+// Fills the tables from the regular scoring functions, so the two paths cannot
+// disagree. Returns None when the rules are not the three-shape A-C/X-Z game.
+pub fn build_table(rules: &Rules) -> Option<ScoreTable> {
+    if rules.shapes.len() != 3 {
+        return None;
+    }
+
+    let mut table = ScoreTable {
+        guide: [0; 9],
+        provoked: [0; 9],
+    };
+
+    for o in 0..3u8 {
+        let opponent = parse_input(rules, &((b'A' + o) as char))?;
+        for c in 0..3u8 {
+            let column = (b'X' + c) as char;
+            let i = (o * 3 + c) as usize;
+
+            let you = parse_input(rules, &column)?;
+            table.guide[i] = score_round(rules, &opponent, &you);
+
+            let provoked = provoke_result(rules, &opponent, parse_required_result(&column)?);
+            table.provoked[i] = score_round(rules, &opponent, &provoked);
+        }
+    }
+
+    Some(table)
+}
+
+#[derive(PartialEq, Debug)]
+pub struct FastScore {
+    pub guide: i32,
+    pub provoked: i32,
+    // Non-empty lines that are not exactly an uppercase shape, a single space
+    // and an uppercase column
+    pub skipped: usize,
+}
+
+pub fn score_bytes(table: &ScoreTable, input: &[u8]) -> FastScore {
+    let mut score = FastScore {
+        guide: 0,
+        provoked: 0,
+        skipped: 0,
+    };
+
+    for line in input.split(|b| *b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        match line {
+            [opponent @ b'A'..=b'C', b' ', column @ b'X'..=b'Z'] => {
+                let i = ((opponent - b'A') * 3 + (column - b'X')) as usize;
+                score.guide += table.guide[i];
+                score.provoked += table.provoked[i];
+            }
+            [] => {}
+            _ => score.skipped += 1,
+        }
+    }
+
+    score
+}
+
+pub fn generate_guide(lines: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut guide = String::with_capacity(lines * 4);
+
+    for _ in 0..lines {
+        guide.push((b'A' + rng.below(3) as u8) as char);
+        guide.push(' ');
+        guide.push((b'X' + rng.below(3) as u8) as char);
+        guide.push('\n');
+    }
+
+    guide
+}
+
+pub fn run_benchmark(
+    rules: &Rules,
+    table: &ScoreTable,
+    lines: usize,
+    seed: u64,
+) -> Result<(), String> {
+    let guide = generate_guide(lines, seed);
+    println!("Generated {lines} lines (seed {seed})");
+
+    let start = Instant::now();
+    let (rounds, _) = parse_rounds(rules, &guide);
+    let slow = (score_guide(rules, &rounds), score_provoked(rules, &rounds));
+    let slow_time = start.elapsed();
+
+    let start = Instant::now();
+    let score = score_bytes(table, guide.as_bytes());
+    let fast = (score.guide, score.provoked);
+    let fast_time = start.elapsed();
+
+    println!("Regular path: {slow:?} in {slow_time:?}");
+    println!("Table path:   {fast:?} in {fast_time:?}");
+    println!(
+        "Speedup: {:.1}x",
+        slow_time.as_secs_f64() / fast_time.as_secs_f64().max(f64::EPSILON)
+    );
+
+    if slow != fast || score.skipped > 0 {
+        return Err(format!(
+            "Table path disagrees with regular path ({} lines skipped)",
+            score.skipped
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fast::*;
+
+    #[test]
+    fn example_input() {
+        let table = build_table(&Rules::classic()).unwrap();
+
+        assert_eq!(
+            score_bytes(&table, b"A Y\r\nB X\r\nC Z\r\n"),
+            FastScore {
+                guide: 15,
+                provoked: 12,
+                skipped: 0
+            }
+        );
+    }
+
+    #[test]
+    fn counts_lines_it_does_not_understand() {
+        let table = build_table(&Rules::classic()).unwrap();
+        let score = score_bytes(&table, b"A Y\nA X Z\na y\nB\tX\nC  Z\n\r\nC Z\r\n");
+
+        assert_eq!((score.guide, score.provoked), (8 + 6, 4 + 7));
+        assert_eq!(score.skipped, 4);
+    }
+
+    #[test]
+    fn matches_regular_path() {
+        let rules = Rules::classic();
+        let table = build_table(&rules).unwrap();
+        let guide = generate_guide(10000, 42);
+        let (rounds, _) = parse_rounds(&rules, &guide);
+
+        assert_eq!(
+            score_bytes(&table, guide.as_bytes()),
+            FastScore {
+                guide: score_guide(&rules, &rounds),
+                provoked: score_provoked(&rules, &rounds),
+                skipped: 0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_other_rules() {
+        let rules = Rules::parse(include_str!("../rules/rpsls.txt")).unwrap();
+
+        assert!(build_table(&rules).is_none());
+    }
+}
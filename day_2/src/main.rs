@@ -1,21 +1,30 @@
-use aoc::get_file_content_or_exit;
+mod encodings;
+mod equilibrium;
+mod explain;
+mod fast;
+mod multiplayer;
+mod parse;
+mod planner;
+mod rules;
+mod simulate;
 
-#[derive(PartialEq, Debug)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissor,
-}
+use std::{fs, io::IsTerminal, process::exit};
 
-fn get_shape_value(shape: &Shape) -> i32 {
-    match shape {
-        Shape::Rock => 1,
-        Shape::Paper => 2,
-        Shape::Scissor => 3,
-    }
+use aoc::{
+    get_file_content_or_exit, get_flag_value, get_flag_value_or_exit, has_flag,
+    parse_flag_value_or_exit,
+};
+use multiplayer::MultiRule;
+use parse::{symbol, tokenize, ParseError};
+use planner::Constraints;
+use rules::{Rules, Shape};
+use simulate::Opponent;
+
+fn get_shape_value(rules: &Rules, shape: &Shape) -> i32 {
+    rules.value(*shape)
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 enum MatchResult {
     Lose,
     Draw,
@@ -30,19 +39,14 @@ fn get_result_value(result: &MatchResult) -> i32 {
     }
 }
 
-fn parse_input(input: &char) -> Option<Shape> {
-    match input {
-        'A' | 'X' => Some(Shape::Rock),
-        'B' | 'Y' => Some(Shape::Paper),
-        'C' | 'Z' => Some(Shape::Scissor),
-        _ => None,
-    }
+fn parse_input(rules: &Rules, input: &char) -> Option<Shape> {
+    rules.shape_for_symbol(*input)
 }
 
-fn check_result(opponent: &Shape, you: &Shape) -> MatchResult {
+fn check_result(rules: &Rules, opponent: &Shape, you: &Shape) -> MatchResult {
     if opponent == you {
         MatchResult::Draw
-    } else if provoke_result(opponent, MatchResult::Lose) == you {
+    } else if rules.beats(*opponent, *you) {
         MatchResult::Lose
     } else {
         MatchResult::Win
@@ -58,57 +62,345 @@ fn parse_required_result(result: &char) -> Option<MatchResult> {
     }
 }
 
-fn provoke_result(opponent: &Shape, result: MatchResult) -> &Shape {
-    match result {
-        MatchResult::Draw => opponent,
-        MatchResult::Lose => match *opponent {
-            Shape::Rock => &Shape::Scissor,
-            Shape::Paper => &Shape::Rock,
-            Shape::Scissor => &Shape::Paper,
-        },
-        MatchResult::Win => match *opponent {
-            Shape::Rock => &Shape::Paper,
-            Shape::Paper => &Shape::Scissor,
-            Shape::Scissor => &Shape::Rock,
-        },
+// When several shapes give the required result, the highest scoring one is
+// picked. With the classic rules there is always exactly one candidate.
+fn provoke_result(rules: &Rules, opponent: &Shape, result: MatchResult) -> Shape {
+    rules
+        .all_shapes()
+        .filter(|you| check_result(rules, opponent, you) == result)
+        .max_by_key(|you| get_shape_value(rules, you))
+        .expect("validated rules can provoke every result")
+}
+
+fn load_rules() -> Rules {
+    let Some(path) = get_flag_value("--rules") else {
+        return Rules::classic();
+    };
+
+    let definition = fs::read_to_string(&path).unwrap_or_else(|err| {
+        println!("{path}: {err}");
+        exit(1);
+    });
+
+    Rules::parse(&definition).unwrap_or_else(|err| {
+        println!("{path}: {err}");
+        exit(1);
+    })
+}
+
+// A line of the strategy guide: the opponent's shape and the raw symbol in the
+// second column, which each part interprets differently
+struct Round {
+    line: usize,
+    opponent: Shape,
+    column: char,
+}
+
+fn parse_round(
+    rules: &Rules,
+    line: usize,
+    opponent: &str,
+    column: &str,
+) -> Result<Round, ParseError> {
+    let shape = parse_input(rules, &symbol(line, opponent)?).ok_or(ParseError::new(
+        line,
+        opponent,
+        "unknown shape",
+    ))?;
+    let column_symbol = symbol(line, column)?;
+
+    if parse_input(rules, &column_symbol).is_none()
+        && parse_required_result(&column_symbol).is_none()
+    {
+        return Err(ParseError::new(line, column, "unknown shape or result"));
     }
+
+    Ok(Round {
+        line,
+        opponent: shape,
+        column: column_symbol,
+    })
+}
+
+fn parse_rounds(rules: &Rules, input: &str) -> (Vec<Round>, Vec<ParseError>) {
+    let mut rounds = vec![];
+    let mut errors = vec![];
+
+    for (line, tokens) in tokenize(input) {
+        let round = match tokens.as_slice() {
+            [opponent, column] => parse_round(rules, line, opponent, column),
+            [_, _, extra, ..] => Err(ParseError::new(line, extra, "unexpected token")),
+            _ => Err(ParseError::new(
+                line,
+                tokens[0],
+                "missing second column after",
+            )),
+        };
+
+        match round {
+            Ok(round) => rounds.push(round),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (rounds, errors)
+}
+
+// Rounds whose second column has no meaning under one of the interpretations
+// are left out of that part's score
+fn column_errors(
+    rounds: &[Round],
+    accepts: impl Fn(&char) -> bool,
+    reason: &str,
+) -> Vec<ParseError> {
+    rounds
+        .iter()
+        .filter(|round| !accepts(&round.column))
+        .map(|round| ParseError::new(round.line, &round.column.to_string(), reason))
+        .collect()
+}
+
+fn score_round(rules: &Rules, opponent: &Shape, you: &Shape) -> i32 {
+    get_result_value(&check_result(rules, opponent, you)) + get_shape_value(rules, you)
+}
+
+fn score_guide(rules: &Rules, rounds: &[Round]) -> i32 {
+    rounds
+        .iter()
+        .filter_map(|round| {
+            let you = parse_input(rules, &round.column)?;
+            Some(score_round(rules, &round.opponent, &you))
+        })
+        .sum()
+}
+
+fn score_provoked(rules: &Rules, rounds: &[Round]) -> i32 {
+    rounds
+        .iter()
+        .filter_map(|round| {
+            let desired_result = parse_required_result(&round.column)?;
+            let shape = provoke_result(rules, &round.opponent, desired_result);
+
+            Some(score_round(rules, &round.opponent, &shape))
+        })
+        .sum()
+}
+
+// Relative weights of the fixed-distribution opponent, one per shape
+fn parse_weights(rules: &Rules, value: Option<String>) -> Result<Vec<f64>, String> {
+    let Some(value) = value else {
+        return Ok(vec![1.0; rules.shapes.len()]);
+    };
+
+    let weights = value
+        .split(',')
+        .map(|w| w.trim().parse::<f64>())
+        .collect::<Result<Vec<f64>, _>>()
+        .map_err(|_| format!("Invalid weights: {value}"))?;
+
+    if weights.len() != rules.shapes.len() {
+        return Err(format!("Expected {} weights", rules.shapes.len()));
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+        return Err(String::from(
+            "Weights must be finite, non-negative and not all zero",
+        ));
+    }
+
+    Ok(weights)
 }
 
 fn main() {
     let data = get_file_content_or_exit();
-    let cleaned = data.replace(' ', "");
+    let rules = load_rules();
+
+    if has_flag("--multiplayer") {
+        let rule_name = get_flag_value("--multi-rule").unwrap_or(String::from("dominant"));
+        let Some(rule) = MultiRule::parse(&rule_name) else {
+            println!("Unknown multi-player rule: {rule_name}");
+            exit(1);
+        };
+
+        let (rounds, errors) = multiplayer::parse_multi_rounds(&rules, &data);
+        parse::report(&errors);
+        multiplayer::print_totals(&multiplayer::score_players(&rules, &rounds, rule));
+        return;
+    }
+
+    if has_flag("--fast") || has_flag("--bench") {
+        let Some(table) = fast::build_table(&rules) else {
+            println!("The table path only supports three shapes written A-C and X-Z");
+            exit(1);
+        };
+
+        if has_flag("--bench") {
+            let lines = get_flag_value_or_exit("--bench-lines", 5_000_000);
+            let seed = get_flag_value_or_exit("--seed", 2022);
+            if let Err(err) = fast::run_benchmark(&rules, &table, lines, seed) {
+                println!("{err}");
+                exit(1);
+            }
+            return;
+        }
+
+        let score = fast::score_bytes(&table, data.as_bytes());
+        if score.skipped == 0 {
+            println!("Total points: {}", score.guide);
+            println!("Provoked point total: {}", score.provoked);
+            return;
+        }
+        eprintln!(
+            "{} lines are not in the plain \"A X\" format, using the regular path",
+            score.skipped
+        );
+    }
+
+    let (rounds, errors) = parse_rounds(&rules, &data);
+    parse::report(&errors);
 
     // Part 1
-    let total: i32 = cleaned
-        .split('\n')
-        .map(|line| {
-            if line.len() > 0 {
-                let mut chars = line.chars();
-                let opponent = parse_input(&chars.next().unwrap()).unwrap();
-                let you = parse_input(&chars.next().unwrap()).unwrap();
-                return get_result_value(&check_result(&opponent, &you)) + get_shape_value(&you);
+    parse::report(&column_errors(
+        &rounds,
+        |c| parse_input(&rules, c).is_some(),
+        "part 1: not a shape",
+    ));
+    println!("Total points: {}", score_guide(&rules, &rounds));
+
+    // Part 2
+    parse::report(&column_errors(
+        &rounds,
+        |c| parse_required_result(c).is_some(),
+        "part 2: not a result",
+    ));
+    println!("Provoked point total: {}", score_provoked(&rules, &rounds));
+
+    if has_flag("--explain") {
+        println!();
+        explain::print_explanation(&rules, &rounds);
+    }
+
+    if has_flag("--encodings") {
+        let mut encodings = encodings::all_encodings(&rules);
+
+        if let Some(path) = get_flag_value("--mapping") {
+            let mapping = fs::read_to_string(&path).unwrap_or_else(|err| {
+                println!("{path}: {err}");
+                exit(1);
+            });
+
+            match encodings::parse_mapping_file(&rules, &mapping) {
+                Ok(user) => encodings.extend(user),
+                Err(err) => {
+                    println!("{path}: {err}");
+                    exit(1);
+                }
             }
-            0
-        })
-        .sum();
-
-    println!("Total points: {}", total);
-
-    let provoked_total: i32 = cleaned
-        .split('\n')
-        .map(|line| {
-            let mut chars = line.chars();
-            if line.len() > 0 {
-                let opponent = parse_input(&chars.next().unwrap()).unwrap();
-                let desired_result = parse_required_result(&chars.next().unwrap()).unwrap();
-                
-                let shape = provoke_result(&opponent, desired_result);
-                let match_result = check_result(&opponent, shape);
-
-                get_result_value(&match_result) + get_shape_value(shape)
-            } else {0}
-        })
-        .sum();
+        }
+
+        let colour = !has_flag("--no-colour") && std::io::stdout().is_terminal();
+        println!();
+        encodings::print_score_table(&rules, &rounds, &encodings, colour);
+    }
+
+    if has_flag("--equilibrium") {
+        println!();
+        equilibrium::print_equilibrium(&rules);
+    }
+
+    if has_flag("--plan") {
+        let constraints = Constraints {
+            max_uses: parse_flag_value_or_exit("--max-uses"),
+            no_repeat: has_flag("--no-repeat"),
+            draws: parse_flag_value_or_exit("--draws"),
+        };
+        let opponents: Vec<Shape> = rounds.iter().map(|round| round.opponent).collect();
 
-    println!("Provoked point total: {}", provoked_total);
+        println!();
+        planner::print_plan(&rules, &opponents, &constraints);
+    }
+
+    if has_flag("--simulate") {
+        let rounds = get_flag_value_or_exit("--rounds", 1000);
+        let games = get_flag_value_or_exit("--games", 100);
+        let seed = get_flag_value_or_exit("--seed", 2022);
+        let weights = parse_weights(&rules, get_flag_value("--weights")).unwrap_or_else(|err| {
+            println!("{err}");
+            exit(1);
+        });
+
+        println!();
+        simulate::print_comparison(
+            &rules,
+            &[
+                Opponent::Fixed(weights),
+                Opponent::Repeat,
+                Opponent::Counter,
+            ],
+            rounds,
+            games,
+            seed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const TEST_INPUT: &str = "A Y\nB X\nC Z\n";
+
+    #[test]
+    fn test_part_1() {
+        let rules = Rules::classic();
+        assert_eq!(score_guide(&rules, &parse_rounds(&rules, TEST_INPUT).0), 15);
+    }
+
+    #[test]
+    fn test_part_2() {
+        let rules = Rules::classic();
+        assert_eq!(
+            score_provoked(&rules, &parse_rounds(&rules, TEST_INPUT).0),
+            12
+        );
+    }
+
+    #[test]
+    fn tolerant_parsing() {
+        let rules = Rules::classic();
+        let (rounds, errors) = parse_rounds(&rules, "a y\r\nB\tX Z\r\n\r\n  C   z \r\nQ X\nAX\n");
+
+        assert_eq!(score_guide(&rules, &rounds), 14);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.line, e.token.as_str()))
+                .collect::<Vec<(usize, &str)>>(),
+            [(2, "Z"), (5, "Q"), (6, "AX")]
+        );
+    }
+
+    #[test]
+    fn five_shape_rules() {
+        let rules = Rules::parse(include_str!("../rules/rpsls.txt")).unwrap();
+        let rock = parse_input(&rules, &'A').unwrap();
+        let spock = parse_input(&rules, &'E').unwrap();
+        let lizard = parse_input(&rules, &'D').unwrap();
+
+        assert_eq!(check_result(&rules, &rock, &spock), MatchResult::Win);
+        assert_eq!(check_result(&rules, &spock, &lizard), MatchResult::Win);
+        assert_eq!(provoke_result(&rules, &rock, MatchResult::Win), spock);
+    }
+
+    #[test]
+    fn rejects_invalid_weights() {
+        let rules = Rules::classic();
+        let weights = |value: &str| parse_weights(&rules, Some(String::from(value)));
+
+        assert_eq!(weights("1, 2,0"), Ok(vec![1.0, 2.0, 0.0]));
+        assert!(weights("1,2").is_err());
+        assert!(weights("1,-1,1").is_err());
+        assert!(weights("0,0,0").is_err());
+        assert!(weights("NaN,1,1").is_err());
+        assert!(weights("inf,1,1").is_err());
+    }
 }
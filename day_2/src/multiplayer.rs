@@ -0,0 +1,211 @@
+// Rounds with more than two players. Every line holds one shape per player.
+
+use crate::parse::{symbol, tokenize, ParseError};
+use crate::rules::{Rules, Shape};
+use crate::{check_result, get_result_value, get_shape_value, parse_input, MatchResult};
+
+#[derive(Clone, Copy)]
+pub enum MultiRule {
+    // Players whose shape beats another shape in the round and is beaten by
+    // none win, everybody else loses. If nobody qualifies, for example when
+    // all shapes appear, the round is a draw.
+    Dominant,
+    // Every player plays a two-player match against every other player
+    Pairwise,
+}
+
+impl MultiRule {
+    pub fn parse(name: &str) -> Option<MultiRule> {
+        match name {
+            "dominant" => Some(MultiRule::Dominant),
+            "pairwise" => Some(MultiRule::Pairwise),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PlayerTotal {
+    pub score: i32,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+pub fn parse_multi_rounds(rules: &Rules, input: &str) -> (Vec<Vec<Shape>>, Vec<ParseError>) {
+    let mut rounds = vec![];
+    let mut errors = vec![];
+
+    for (line, tokens) in tokenize(input) {
+        if tokens.len() < 2 {
+            errors.push(ParseError::new(
+                line,
+                tokens[0],
+                "expected at least two shapes, found only",
+            ));
+            continue;
+        }
+
+        let round = tokens
+            .iter()
+            .map(|token| {
+                parse_input(rules, &symbol(line, token)?).ok_or(ParseError::new(
+                    line,
+                    token,
+                    "unknown shape",
+                ))
+            })
+            .collect();
+
+        match round {
+            Ok(round) => rounds.push(round),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (rounds, errors)
+}
+
+// Outcome of every player in a single round
+fn play_round(rules: &Rules, round: &[Shape], rule: MultiRule) -> Vec<(MatchResult, i32)> {
+    match rule {
+        MultiRule::Dominant => {
+            let winners: Vec<bool> = round
+                .iter()
+                .map(|shape| {
+                    round.iter().any(|other| rules.beats(*shape, *other))
+                        && !round.iter().any(|other| rules.beats(*other, *shape))
+                })
+                .collect();
+
+            let any_winner = winners.contains(&true);
+
+            winners
+                .iter()
+                .map(|is_winner| {
+                    let result = match (any_winner, is_winner) {
+                        (false, _) => MatchResult::Draw,
+                        (true, true) => MatchResult::Win,
+                        (true, false) => MatchResult::Lose,
+                    };
+                    (result, get_result_value(&result))
+                })
+                .collect()
+        }
+        MultiRule::Pairwise => round
+            .iter()
+            .enumerate()
+            .map(|(i, shape)| {
+                let results: Vec<MatchResult> = round
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, other)| check_result(rules, other, shape))
+                    .collect();
+                let points = results.iter().map(get_result_value).sum::<i32>();
+                let wins = results.iter().filter(|r| **r == MatchResult::Win).count();
+                let losses = results.iter().filter(|r| **r == MatchResult::Lose).count();
+
+                let overall = match wins.cmp(&losses) {
+                    std::cmp::Ordering::Greater => MatchResult::Win,
+                    std::cmp::Ordering::Equal => MatchResult::Draw,
+                    std::cmp::Ordering::Less => MatchResult::Lose,
+                };
+                (overall, points)
+            })
+            .collect(),
+    }
+}
+
+pub fn score_players(rules: &Rules, rounds: &[Vec<Shape>], rule: MultiRule) -> Vec<PlayerTotal> {
+    let mut totals: Vec<PlayerTotal> = vec![];
+
+    for round in rounds.iter() {
+        while totals.len() < round.len() {
+            totals.push(PlayerTotal::default());
+        }
+
+        for ((shape, (result, points)), total) in round
+            .iter()
+            .zip(play_round(rules, round, rule))
+            .zip(totals.iter_mut())
+        {
+            total.score += get_shape_value(rules, shape) + points;
+            match result {
+                MatchResult::Win => total.wins += 1,
+                MatchResult::Draw => total.draws += 1,
+                MatchResult::Lose => total.losses += 1,
+            }
+        }
+    }
+
+    totals
+}
+
+pub fn print_totals(totals: &[PlayerTotal]) {
+    for (i, total) in totals.iter().enumerate() {
+        println!(
+            "Player {}: {} points ({} won, {} drawn, {} lost)",
+            i + 1,
+            total.score,
+            total.wins,
+            total.draws,
+            total.losses
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multiplayer::*;
+
+    fn scores(input: &str, rule: MultiRule) -> Vec<i32> {
+        let rules = Rules::classic();
+        let (rounds, _) = parse_multi_rounds(&rules, input);
+
+        score_players(&rules, &rounds, rule)
+            .iter()
+            .map(|t| t.score)
+            .collect()
+    }
+
+    #[test]
+    fn all_shapes_draw() {
+        assert_eq!(scores("A B C", MultiRule::Dominant), [4, 5, 6]);
+    }
+
+    #[test]
+    fn winners_beat_losers() {
+        assert_eq!(scores("A B Y", MultiRule::Dominant), [1, 8, 8]);
+        assert_eq!(scores("A A X Z", MultiRule::Dominant), [7, 7, 7, 3]);
+    }
+
+    #[test]
+    fn pairwise() {
+        assert_eq!(scores("A B B", MultiRule::Pairwise), [1, 11, 11]);
+    }
+
+    #[test]
+    fn uneven_player_counts() {
+        assert_eq!(scores("A Y\nA B C", MultiRule::Dominant), [5, 13, 6]);
+    }
+
+    #[test]
+    fn rejects_unknown_shape() {
+        let (rounds, errors) = parse_multi_rounds(&Rules::classic(), "A B\nA Q");
+
+        assert_eq!(rounds.len(), 1);
+        assert_eq!(errors[0].line, 2);
+        assert_eq!(errors[0].token, "Q");
+    }
+
+    #[test]
+    fn rejects_single_player() {
+        let (rounds, errors) = parse_multi_rounds(&Rules::classic(), "A B\nC\nB C A");
+
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+        assert_eq!(errors[0].token, "C");
+    }
+}
@@ -0,0 +1,76 @@
+// Tolerant tokenizer for the strategy guide. Lines may use CRLF endings and
+// any mix of whitespace, and symbols are case-insensitive.
+
+use std::fmt;
+
+pub struct ParseError {
+    pub line: usize,
+    pub token: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, token: &str, reason: &str) -> ParseError {
+        ParseError {
+            line,
+            token: token.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {} '{}'", self.line, self.reason, self.token)
+    }
+}
+
+// Yields the 1-based line number and tokens of every non-blank line
+pub fn tokenize(input: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split_whitespace().collect::<Vec<&str>>()))
+        .filter(|(_, tokens)| !tokens.is_empty())
+}
+
+pub fn symbol(line: usize, token: &str) -> Result<char, ParseError> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c.to_ascii_uppercase()),
+        _ => Err(ParseError::new(
+            line,
+            token,
+            "expected a single letter, found",
+        )),
+    }
+}
+
+pub fn report(errors: &[ParseError]) {
+    errors.iter().for_each(|err| eprintln!("Skipped {err}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::*;
+
+    #[test]
+    fn tokenize_handles_whitespace() {
+        let lines: Vec<(usize, Vec<&str>)> = tokenize("A Y\r\n\r\n  B\t X  \r\nC Z").collect();
+
+        assert_eq!(
+            lines,
+            [
+                (1, vec!["A", "Y"]),
+                (3, vec!["B", "X"]),
+                (4, vec!["C", "Z"])
+            ]
+        );
+    }
+
+    #[test]
+    fn symbols_are_case_insensitive() {
+        assert_eq!(symbol(1, "a").ok(), Some('A'));
+        assert!(symbol(1, "AY").is_err());
+    }
+}
@@ -0,0 +1,571 @@
+// Best possible play against a known opponent sequence under constraints on
+// our own moves
+
+use std::collections::HashMap;
+
+use crate::rules::{Rules, Shape};
+use crate::{check_result, score_round, MatchResult};
+
+#[derive(Default)]
+pub struct Constraints {
+    // Each shape may be played at most this many times
+    pub max_uses: Option<usize>,
+    // The same shape may not be played twice in a row
+    pub no_repeat: bool,
+    // Exactly this many rounds must end in a draw
+    pub draws: Option<usize>,
+}
+
+pub struct Plan {
+    pub moves: Vec<Shape>,
+    pub score: i32,
+}
+
+// Only the parts of the history that an active constraint depends on are kept
+// in the state, so unconstrained planning has a single state per round. A
+// state is packed into one integer in mixed radix: the last shape, then the
+// number of draws, then the uses of every shape.
+struct Encoding {
+    last_radix: u128,
+    draws_radix: u128,
+    uses_radix: u128,
+}
+
+impl Encoding {
+    fn new(shapes: usize, constraints: &Constraints) -> Option<Encoding> {
+        let encoding = Encoding {
+            last_radix: if constraints.no_repeat {
+                shapes as u128 + 1
+            } else {
+                1
+            },
+            draws_radix: constraints.draws.map_or(1, |d| d as u128 + 1),
+            uses_radix: constraints.max_uses.map_or(1, |m| m as u128 + 1),
+        };
+
+        // Make sure the largest state fits
+        (0..shapes).try_fold(encoding.last_radix * encoding.draws_radix, |acc, _| {
+            acc.checked_mul(encoding.uses_radix)
+        })?;
+        Some(encoding)
+    }
+
+    fn last(&self, state: u128) -> Option<Shape> {
+        match state % self.last_radix {
+            0 => None,
+            last => Some(Shape(last as usize - 1)),
+        }
+    }
+
+    fn draws(&self, state: u128) -> usize {
+        (state / self.last_radix % self.draws_radix) as usize
+    }
+
+    fn uses_step(&self, shape: Shape) -> u128 {
+        self.last_radix * self.draws_radix * self.uses_radix.pow(shape.0 as u32)
+    }
+
+    fn uses(&self, state: u128, shape: Shape) -> usize {
+        (state / self.uses_step(shape) % self.uses_radix) as usize
+    }
+
+    fn with_last(&self, state: u128, shape: Shape) -> u128 {
+        if self.last_radix == 1 {
+            return state;
+        }
+        state - state % self.last_radix + shape.0 as u128 + 1
+    }
+}
+
+struct Edge {
+    to: usize,
+    capacity: usize,
+    cost: i64,
+}
+
+// Adds an edge and its reverse, returning the index of the edge
+fn add_edge(edges: &mut Vec<Edge>, from: usize, to: usize, capacity: usize, cost: i64) -> usize {
+    edges.push(Edge { to, capacity, cost });
+    edges.push(Edge {
+        to: from,
+        capacity: 0,
+        cost: -cost,
+    });
+    edges.len() - 2
+}
+
+// Cheapest flow of as many units as possible from the first node to the last.
+// Edges are stored in pairs, every edge followed by its residual reverse edge.
+fn min_cost_flow(nodes: usize, edges: &mut [Edge]) {
+    let (source, sink) = (0, nodes - 1);
+
+    loop {
+        // Bellman-Ford, as the reverse edges carry negative costs
+        let mut dist: Vec<Option<i64>> = vec![None; nodes];
+        let mut via: Vec<Option<usize>> = vec![None; nodes];
+        dist[source] = Some(0);
+
+        for _ in 0..nodes {
+            let mut changed = false;
+            for (i, edge) in edges.iter().enumerate() {
+                let from = edges[i ^ 1].to;
+                let Some(d) = dist[from] else {
+                    continue;
+                };
+                if edge.capacity > 0 && dist[edge.to].is_none_or(|old| d + edge.cost < old) {
+                    dist[edge.to] = Some(d + edge.cost);
+                    via[edge.to] = Some(i);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        if dist[sink].is_none() {
+            return;
+        }
+
+        let mut path = vec![];
+        let mut node = sink;
+        while let Some(i) = via[node] {
+            path.push(i);
+            node = edges[i ^ 1].to;
+        }
+
+        let amount = path.iter().map(|i| edges[*i].capacity).min().unwrap_or(0);
+        for i in path {
+            edges[i].capacity -= amount;
+            edges[i ^ 1].capacity += amount;
+        }
+    }
+}
+
+// Without the no-repeat rule the order of the rounds does not matter, so a
+// usage cap alone is a transportation problem: every round against opponent
+// shape o is sent to one of our shapes, each of which takes at most `max`
+// rounds. It is solved as a min-cost flow over the shapes only, so its size
+// does not depend on the number of rounds.
+fn plan_with_cap(rules: &Rules, opponents: &[Shape], max: usize) -> Plan {
+    let shapes = rules.shapes.len();
+    // Source, opponent shapes, our shapes, sink
+    let nodes = 2 * shapes + 2;
+    let mut edges: Vec<Edge> = vec![];
+    // Index of the edge from every opponent shape to every shape of ours
+    let mut reply_edges = vec![vec![0; shapes]; shapes];
+
+    for opponent in rules.all_shapes() {
+        let rounds = opponents.iter().filter(|o| **o == opponent).count();
+        add_edge(&mut edges, 0, 1 + opponent.0, rounds, 0);
+
+        for you in rules.all_shapes() {
+            let score = score_round(rules, &opponent, &you) as i64;
+            reply_edges[opponent.0][you.0] = add_edge(
+                &mut edges,
+                1 + opponent.0,
+                1 + shapes + you.0,
+                rounds,
+                -score,
+            );
+        }
+    }
+    for you in rules.all_shapes() {
+        add_edge(&mut edges, 1 + shapes + you.0, nodes - 1, max, 0);
+    }
+
+    min_cost_flow(nodes, &mut edges);
+
+    // How often we reply to each opponent shape with each of ours, which is
+    // the capacity gained by the reverse edges
+    let mut replies: Vec<Vec<usize>> = reply_edges
+        .iter()
+        .map(|row| row.iter().map(|i| edges[i ^ 1].capacity).collect())
+        .collect();
+
+    let moves: Vec<Shape> = opponents
+        .iter()
+        .map(|opponent| {
+            let you = (0..shapes)
+                .find(|you| replies[opponent.0][*you] > 0)
+                .expect("the flow covers every round");
+            replies[opponent.0][you] -= 1;
+            Shape(you)
+        })
+        .collect();
+    let score = opponents
+        .iter()
+        .zip(moves.iter())
+        .map(|(opponent, you)| score_round(rules, opponent, you))
+        .sum();
+
+    Plan { moves, score }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum PlanError {
+    // No move sequence satisfies the constraints
+    Infeasible,
+    // Going through the rounds would need more than MAX_STATES states
+    TooLarge,
+}
+
+// Limit on the states summed over all rounds. Each one keeps five bytes of
+// back-pointers until the end, so this stays around 100MB.
+const MAX_STATES: f64 = 20_000_000.0;
+
+// Upper bound on the number of states summed over all rounds. After r rounds
+// the uses add up to r, so only the ways to split r into per-shape counts of
+// at most max_uses are counted.
+fn count_states(shapes: usize, rounds: usize, constraints: &Constraints) -> f64 {
+    let uses = match constraints.max_uses {
+        None => vec![1.0; rounds + 1],
+        Some(max) => {
+            let mut ways = vec![0.0; rounds + 1];
+            ways[0] = 1.0;
+            for _ in 0..shapes {
+                // Adds one more shape, used between 0 and max times
+                let mut window = 0.0;
+                ways = (0..=rounds)
+                    .map(|t| {
+                        window += ways[t];
+                        if t > max {
+                            window -= ways[t - max - 1];
+                        }
+                        window
+                    })
+                    .collect();
+            }
+            ways
+        }
+    };
+
+    let last = if constraints.no_repeat {
+        shapes as f64
+    } else {
+        1.0
+    };
+    (1..=rounds)
+        .map(|r| {
+            let draws = constraints.draws.map_or(1, |d| d.min(r) + 1);
+            uses[r] * last * draws as f64
+        })
+        .sum()
+}
+
+// Plans with the flow when only the usage cap applies, and otherwise with
+// dynamic programming over (round, state). Only the states of the current
+// round are kept, and for earlier rounds just the move and predecessor of
+// every state. With a cap and the no-repeat rule the states grow like
+// (max_uses + 1)^(shapes - 1), so inputs needing more than MAX_STATES are
+// refused up front.
+pub fn plan(
+    rules: &Rules,
+    opponents: &[Shape],
+    constraints: &Constraints,
+) -> Result<Plan, PlanError> {
+    let shapes = rules.shapes.len();
+    let rounds = opponents.len();
+    // A cap at or above the number of rounds constrains nothing
+    let constraints = Constraints {
+        max_uses: constraints.max_uses.filter(|max| *max < rounds),
+        no_repeat: constraints.no_repeat,
+        draws: constraints.draws,
+    };
+
+    if let Some(max) = constraints.max_uses {
+        if max.saturating_mul(shapes) < rounds {
+            return Err(PlanError::Infeasible);
+        }
+    }
+    if constraints.draws.is_some_and(|draws| draws > rounds) {
+        return Err(PlanError::Infeasible);
+    }
+
+    if let (Some(max), false, None) = (
+        constraints.max_uses,
+        constraints.no_repeat,
+        constraints.draws,
+    ) {
+        return Ok(plan_with_cap(rules, opponents, max));
+    }
+
+    if count_states(shapes, rounds, &constraints) > MAX_STATES
+        || Encoding::new(shapes, &constraints).is_none()
+    {
+        return Err(PlanError::TooLarge);
+    }
+    plan_by_rounds(rules, opponents, &constraints).ok_or(PlanError::Infeasible)
+}
+
+fn plan_by_rounds(rules: &Rules, opponents: &[Shape], constraints: &Constraints) -> Option<Plan> {
+    let shapes = rules.shapes.len();
+    let encoding = Encoding::new(shapes, constraints)?;
+    let mut layer: Vec<(u128, i32)> = vec![(0, 0)];
+    // Predecessor index and move of every state of every round
+    let mut parents: Vec<Vec<u32>> = vec![];
+    let mut moves: Vec<Vec<u8>> = vec![];
+
+    for (round, opponent) in opponents.iter().enumerate() {
+        let remaining = opponents.len() - round - 1;
+        let mut next: Vec<(u128, i32)> = vec![];
+        let mut next_parents: Vec<u32> = vec![];
+        let mut next_moves: Vec<u8> = vec![];
+        let mut index: HashMap<u128, usize> = HashMap::new();
+
+        for (parent, (state, score)) in layer.iter().enumerate() {
+            for shape in rules.all_shapes() {
+                if constraints.no_repeat && encoding.last(*state) == Some(shape) {
+                    continue;
+                }
+                if let Some(max) = constraints.max_uses {
+                    if encoding.uses(*state, shape) >= max {
+                        continue;
+                    }
+                }
+
+                let mut next_state = encoding.with_last(*state, shape);
+                if let Some(required) = constraints.draws {
+                    let mut draws = encoding.draws(*state);
+                    if check_result(rules, opponent, &shape) == MatchResult::Draw {
+                        draws += 1;
+                        next_state += encoding.last_radix;
+                    }
+                    if draws > required || draws + remaining < required {
+                        continue;
+                    }
+                }
+                if constraints.max_uses.is_some() {
+                    next_state += encoding.uses_step(shape);
+                }
+
+                let score = score + score_round(rules, opponent, &shape);
+                let i = *index.entry(next_state).or_insert_with(|| {
+                    next.push((next_state, i32::MIN));
+                    next_parents.push(0);
+                    next_moves.push(0);
+                    next.len() - 1
+                });
+                if next[i].1 < score {
+                    next[i].1 = score;
+                    next_parents[i] = parent as u32;
+                    next_moves[i] = shape.0 as u8;
+                }
+            }
+        }
+
+        if next.is_empty() {
+            return None;
+        }
+        layer = next;
+        parents.push(next_parents);
+        moves.push(next_moves);
+    }
+
+    let (mut current, (_, score)) = layer
+        .iter()
+        .enumerate()
+        .max_by_key(|(i, (_, score))| (*score, std::cmp::Reverse(*i)))?;
+
+    let mut plan = vec![];
+    for (parents, moves) in parents.iter().zip(moves.iter()).rev() {
+        plan.push(Shape(moves[current] as usize));
+        current = parents[current] as usize;
+    }
+    plan.reverse();
+
+    Some(Plan {
+        moves: plan,
+        score: *score,
+    })
+}
+
+pub fn print_plan(rules: &Rules, opponents: &[Shape], constraints: &Constraints) {
+    let plan = match plan(rules, opponents, constraints) {
+        Ok(plan) => plan,
+        Err(PlanError::Infeasible) => {
+            println!("No move sequence satisfies the constraints");
+            return;
+        }
+        Err(PlanError::TooLarge) => {
+            println!("The guide is too long to plan every round under these constraints");
+            return;
+        }
+    };
+
+    println!("Planned score: {}", plan.score);
+    for shape in rules.all_shapes() {
+        let uses = plan.moves.iter().filter(|s| **s == shape).count();
+        println!("  {}: played {uses} times", rules.name(shape));
+    }
+    println!(
+        "Moves: {}",
+        plan.moves
+            .iter()
+            .map(|s| rules.shapes[s.0].own)
+            .collect::<String>()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::planner::*;
+    use aoc::Rng;
+
+    const ROCKS: [Shape; 3] = [Shape(0), Shape(0), Shape(0)];
+
+    #[test]
+    fn unconstrained_plays_best_reply() {
+        let rules = Rules::classic();
+        let plan = plan(&rules, &ROCKS, &Constraints::default()).unwrap();
+
+        assert_eq!(plan.score, 24);
+        assert_eq!(plan.moves, [Shape(1), Shape(1), Shape(1)]);
+    }
+
+    #[test]
+    fn no_repeat() {
+        let rules = Rules::classic();
+        let constraints = Constraints {
+            no_repeat: true,
+            ..Default::default()
+        };
+        let plan = plan(&rules, &ROCKS, &constraints).unwrap();
+
+        assert_eq!(plan.score, 20);
+        assert_eq!(plan.moves, [Shape(1), Shape(0), Shape(1)]);
+    }
+
+    #[test]
+    fn max_uses() {
+        let rules = Rules::classic();
+        let constraints = Constraints {
+            max_uses: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(plan(&rules, &ROCKS, &constraints).unwrap().score, 15);
+        assert_eq!(
+            plan(&rules, &[Shape(0); 4], &constraints).err(),
+            Some(PlanError::Infeasible)
+        );
+    }
+
+    #[test]
+    fn required_draws() {
+        let rules = Rules::classic();
+        let constraints = Constraints {
+            draws: Some(2),
+            ..Default::default()
+        };
+        let plan = plan(&rules, &ROCKS, &constraints).unwrap();
+
+        assert_eq!(plan.score, 16);
+        assert_eq!(plan.moves.iter().filter(|s| **s == Shape(0)).count(), 2);
+    }
+
+    #[test]
+    fn flow_matches_rounds() {
+        let rules = Rules::classic();
+        let mut rng = Rng::new(7);
+        let constraints = Constraints {
+            max_uses: Some(12),
+            ..Default::default()
+        };
+
+        for _ in 0..20 {
+            let opponents: Vec<Shape> = (0..30).map(|_| Shape(rng.below(3))).collect();
+
+            assert_eq!(
+                plan_with_cap(&rules, &opponents, 12).score,
+                plan_by_rounds(&rules, &opponents, &constraints)
+                    .unwrap()
+                    .score
+            );
+        }
+    }
+
+    #[test]
+    fn long_guide_with_cap() {
+        let rules = Rules::classic();
+        let opponents: Vec<Shape> = (0..600).map(|i| Shape(i % 3)).collect();
+        let constraints = Constraints {
+            max_uses: Some(210),
+            ..Default::default()
+        };
+        let mixed = plan(&rules, &opponents, &constraints).unwrap();
+
+        // Winning every round needs 200 of each shape, which fits the cap
+        assert_eq!(mixed.score, 600 * 6 + 200 * (1 + 2 + 3));
+        assert!(rules
+            .all_shapes()
+            .all(|s| mixed.moves.iter().filter(|m| **m == s).count() <= 210));
+
+        let constraints = Constraints {
+            max_uses: Some(150),
+            ..Default::default()
+        };
+        let rocks = [Shape(0); 400];
+        let capped = plan(&rules, &rocks, &constraints).unwrap();
+
+        // 150 wins with paper, 150 draws with rock, 100 losses with scissors
+        assert_eq!(capped.score, 150 * 8 + 150 * 4 + 100 * 3);
+
+        // The no-repeat rule keeps the order relevant, so this one goes
+        // through the rounds
+        let constraints = Constraints {
+            max_uses: Some(75),
+            no_repeat: true,
+            ..Default::default()
+        };
+        let alternating = plan(&rules, &rocks[..200], &constraints).unwrap();
+
+        // Paper and rock in turn, with scissors only where needed
+        assert_eq!(alternating.score, 75 * 8 + 75 * 4 + 50 * 3);
+    }
+
+    #[test]
+    fn loose_caps_constrain_nothing() {
+        let rules = Rules::parse(include_str!("../rules/rpsls.txt")).unwrap();
+        let mut rng = Rng::new(5);
+        let opponents: Vec<Shape> = (0..20).map(|_| Shape(rng.below(5))).collect();
+        let score = |max_uses| {
+            let constraints = Constraints {
+                max_uses,
+                no_repeat: true,
+                ..Default::default()
+            };
+            plan(&rules, &opponents, &constraints).ok().map(|p| p.score)
+        };
+
+        assert!(score(None).is_some());
+        assert_eq!(score(Some(20)), score(None));
+        assert_eq!(score(Some(100_000_000)), score(None));
+        assert_eq!(score(Some(usize::MAX)), score(None));
+    }
+
+    #[test]
+    fn refuses_too_many_states() {
+        let rules = Rules::classic();
+        let opponents: Vec<Shape> = (0..1000).map(|i| Shape(i % 3)).collect();
+        let constraints = Constraints {
+            max_uses: Some(400),
+            no_repeat: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            plan(&rules, &opponents, &constraints).err(),
+            Some(PlanError::TooLarge)
+        );
+        // Impossible draw counts are caught before the size
+        let constraints = Constraints {
+            draws: Some(1001),
+            ..constraints
+        };
+        assert_eq!(
+            plan(&rules, &opponents, &constraints).err(),
+            Some(PlanError::Infeasible)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+// Rule definitions for cyclic-dominance games. A definition lists every shape
+// with its score and input symbols, followed by the beat relations:
+//
+//     shape Rock 1 A X
+//     beats Rock Scissor
+//
+// Blank lines and lines starting with '#' are ignored. Symbols are
+// case-insensitive.
+
+pub const CLASSIC_RULES: &str = "\
+shape Rock 1 A X
+shape Paper 2 B Y
+shape Scissor 3 C Z
+beats Rock Scissor
+beats Paper Rock
+beats Scissor Paper
+";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Shape(pub usize);
+
+#[derive(Debug)]
+pub struct ShapeDef {
+    pub name: String,
+    pub value: i32,
+    pub opponent: char,
+    pub own: char,
+}
+
+#[derive(Debug)]
+pub struct Rules {
+    pub shapes: Vec<ShapeDef>,
+    // beats[a][b] is true when shape a beats shape b
+    beats: Vec<Vec<bool>>,
+}
+
+impl Rules {
+    pub fn classic() -> Rules {
+        Rules::parse(CLASSIC_RULES).expect("classic rules")
+    }
+
+    pub fn parse(definition: &str) -> Result<Rules, String> {
+        let mut shapes: Vec<ShapeDef> = vec![];
+        let mut relations: Vec<(usize, String, String)> = vec![];
+
+        for (i, line) in definition.lines().enumerate() {
+            let line_num = i + 1;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields.as_slice() {
+                [] => {}
+                [comment, ..] if comment.starts_with('#') => {}
+                ["shape", name, value, opponent, own] => {
+                    let value = value
+                        .parse::<i32>()
+                        .map_err(|_| format!("line {line_num}: invalid score '{value}'"))?;
+                    let opponent = parse_symbol(opponent, line_num)?;
+                    let own = parse_symbol(own, line_num)?;
+
+                    if shapes.iter().any(|s| s.name == *name) {
+                        return Err(format!("line {line_num}: duplicate shape '{name}'"));
+                    }
+
+                    shapes.push(ShapeDef {
+                        name: name.to_string(),
+                        value,
+                        opponent,
+                        own,
+                    });
+                }
+                ["beats", winner, losers @ ..] if !losers.is_empty() => {
+                    losers.iter().for_each(|loser| {
+                        relations.push((line_num, winner.to_string(), loser.to_string()))
+                    });
+                }
+                _ => return Err(format!("line {line_num}: cannot parse '{}'", line.trim())),
+            }
+        }
+
+        let mut beats = vec![vec![false; shapes.len()]; shapes.len()];
+        let find = |name: &str, line_num: usize| {
+            shapes
+                .iter()
+                .position(|s| s.name == name)
+                .ok_or(format!("line {line_num}: unknown shape '{name}'"))
+        };
+
+        for (line_num, winner, loser) in relations.iter() {
+            let w = find(winner, *line_num)?;
+            let l = find(loser, *line_num)?;
+            if w == l {
+                return Err(format!("line {line_num}: '{winner}' cannot beat itself"));
+            }
+            beats[w][l] = true;
+        }
+
+        let rules = Rules { shapes, beats };
+        rules.validate()?;
+
+        Ok(rules)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.shapes.len() < 3 {
+            return Err(String::from("at least three shapes are required"));
+        }
+
+        for (i, a) in self.shapes.iter().enumerate() {
+            for (j, b) in self.shapes.iter().enumerate().skip(i + 1) {
+                // Symbols are looked up in both columns, so they must be
+                // unique across them
+                let a_symbols = [a.opponent, a.own];
+                if a_symbols.contains(&b.opponent) || a_symbols.contains(&b.own) {
+                    return Err(format!("'{}' and '{}' share a symbol", a.name, b.name));
+                }
+
+                match (self.beats[i][j], self.beats[j][i]) {
+                    (true, true) => {
+                        return Err(format!("'{}' and '{}' beat each other", a.name, b.name))
+                    }
+                    (false, false) => {
+                        return Err(format!("no relation between '{}' and '{}'", a.name, b.name))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Every shape must be both beatable and able to win, otherwise some
+        // required results cannot be provoked
+        for shape in self.all_shapes() {
+            if !self.all_shapes().any(|other| self.beats(shape, other)) {
+                return Err(format!("'{}' beats no shape", self.name(shape)));
+            }
+            if !self.all_shapes().any(|other| self.beats(other, shape)) {
+                return Err(format!("'{}' is never beaten", self.name(shape)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn all_shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn beats(&self, winner: Shape, loser: Shape) -> bool {
+        self.beats[winner.0][loser.0]
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].name
+    }
+
+    pub fn value(&self, shape: Shape) -> i32 {
+        self.shapes[shape.0].value
+    }
+
+    pub fn shape_for_symbol(&self, symbol: char) -> Option<Shape> {
+        self.shapes
+            .iter()
+            .position(|s| s.opponent == symbol || s.own == symbol)
+            .map(Shape)
+    }
+}
+
+fn parse_symbol(field: &str, line_num: usize) -> Result<char, String> {
+    let mut chars = field.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c.to_ascii_uppercase()),
+        _ => Err(format!(
+            "line {line_num}: symbol '{field}' must be one character"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::*;
+
+    #[test]
+    fn classic_rules() {
+        let rules = Rules::classic();
+
+        assert_eq!(rules.shapes.len(), 3);
+        assert!(rules.beats(Shape(0), Shape(2)));
+        assert!(!rules.beats(Shape(2), Shape(0)));
+        assert_eq!(rules.shape_for_symbol('Y'), Some(Shape(1)));
+    }
+
+    #[test]
+    fn rejects_symbols_shared_across_columns() {
+        let def = "shape Rock 1 A B\nshape Paper 2 B Y\nshape Scissor 3 C Z\n\
+                   beats Rock Scissor\nbeats Paper Rock\nbeats Scissor Paper";
+
+        assert!(Rules::parse(def).is_err());
+        assert!(Rules::parse(&def.replace("A B", "R R").replace("B Y", "P Y")).is_ok());
+    }
+
+    #[test]
+    fn rejects_incomplete_relations() {
+        let def = "shape A 1 A X\nshape B 2 B Y\nshape C 3 C Z\nbeats A B\nbeats B C";
+
+        assert!(Rules::parse(def).is_err());
+    }
+
+    #[test]
+    fn rejects_dominant_shape() {
+        let def = "shape A 1 A X\nshape B 2 B Y\nshape C 3 C Z\nbeats A B C\nbeats B C";
+
+        assert!(Rules::parse(def).is_err());
+    }
+}
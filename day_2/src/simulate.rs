@@ -0,0 +1,242 @@
+// Monte-Carlo tournaments between our strategies and a few opponent models
+
+use aoc::Rng;
+
+use crate::rules::{Rules, Shape};
+use crate::{check_result, get_result_value, get_shape_value, provoke_result, MatchResult};
+
+pub enum Opponent {
+    // Picks shapes at random with the given relative weights
+    Fixed(Vec<f64>),
+    // Repeats its previous move
+    Repeat,
+    // Plays whatever beats our previous move
+    Counter,
+}
+
+pub enum Strategy {
+    Always(Shape),
+    Uniform,
+    // Plays whatever beats the opponent's previous move
+    BeatLast,
+    // Assumes the opponent counters our previous move, and beats that
+    BeatCounter,
+}
+
+#[derive(Default)]
+struct History {
+    ours: Option<Shape>,
+    theirs: Option<Shape>,
+}
+
+// The shape that wins against `shape`
+fn beats(rules: &Rules, shape: &Shape) -> Shape {
+    provoke_result(rules, shape, MatchResult::Win)
+}
+
+fn random_shape(rules: &Rules, rng: &mut Rng) -> Shape {
+    Shape(rng.below(rules.shapes.len()))
+}
+
+impl Opponent {
+    pub fn name(&self) -> String {
+        match self {
+            Opponent::Fixed(weights) => format!(
+                "fixed {}",
+                weights
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<String>>()
+                    .join(":")
+            ),
+            Opponent::Repeat => String::from("repeat"),
+            Opponent::Counter => String::from("counter"),
+        }
+    }
+
+    fn choose(&self, rules: &Rules, rng: &mut Rng, history: &History) -> Shape {
+        match (self, history.theirs, history.ours) {
+            (Opponent::Fixed(weights), ..) => {
+                let mut pick = rng.next_f64() * weights.iter().sum::<f64>();
+                for (i, weight) in weights.iter().enumerate() {
+                    if pick < *weight {
+                        return Shape(i);
+                    }
+                    pick -= weight;
+                }
+                Shape(weights.len() - 1)
+            }
+            (Opponent::Repeat, Some(last), _) => last,
+            (Opponent::Counter, _, Some(ours)) => beats(rules, &ours),
+            _ => random_shape(rules, rng),
+        }
+    }
+}
+
+impl Strategy {
+    pub fn name(&self, rules: &Rules) -> String {
+        match self {
+            Strategy::Always(shape) => format!("always {}", rules.name(*shape)),
+            Strategy::Uniform => String::from("uniform"),
+            Strategy::BeatLast => String::from("beat last"),
+            Strategy::BeatCounter => String::from("beat counter"),
+        }
+    }
+
+    fn choose(&self, rules: &Rules, rng: &mut Rng, history: &History) -> Shape {
+        match (self, history.theirs, history.ours) {
+            (Strategy::Always(shape), ..) => *shape,
+            (Strategy::BeatLast, Some(theirs), _) => beats(rules, &theirs),
+            (Strategy::BeatCounter, _, Some(ours)) => beats(rules, &beats(rules, &ours)),
+            _ => random_shape(rules, rng),
+        }
+    }
+
+    pub fn all(rules: &Rules) -> Vec<Strategy> {
+        let mut strategies: Vec<Strategy> = rules.all_shapes().map(Strategy::Always).collect();
+        strategies.extend([Strategy::Uniform, Strategy::BeatLast, Strategy::BeatCounter]);
+        strategies
+    }
+}
+
+// Plays `rounds` rounds and returns our mean score per round
+pub fn simulate(
+    rules: &Rules,
+    opponent: &Opponent,
+    strategy: &Strategy,
+    rounds: usize,
+    seed: u64,
+) -> f64 {
+    let mut rng = Rng::new(seed);
+    let mut history = History::default();
+    let mut total: i64 = 0;
+
+    for _ in 0..rounds {
+        let theirs = opponent.choose(rules, &mut rng, &history);
+        let ours = strategy.choose(rules, &mut rng, &history);
+
+        total += (get_result_value(&check_result(rules, &theirs, &ours))
+            + get_shape_value(rules, &ours)) as i64;
+
+        history = History {
+            ours: Some(ours),
+            theirs: Some(theirs),
+        };
+    }
+
+    total as f64 / rounds.max(1) as f64
+}
+
+// Mean score per round over `games` games, each played with its own seed
+// drawn from `seed`. Opponents like Repeat only use chance for their first
+// move, so a single game would hinge on that one draw.
+pub fn mean_score(
+    rules: &Rules,
+    opponent: &Opponent,
+    strategy: &Strategy,
+    rounds: usize,
+    games: usize,
+    seed: u64,
+) -> f64 {
+    let mut seeds = Rng::new(seed);
+    let total: f64 = (0..games)
+        .map(|_| simulate(rules, opponent, strategy, rounds, seeds.next_u64()))
+        .sum();
+
+    total / games.max(1) as f64
+}
+
+pub fn print_comparison(
+    rules: &Rules,
+    opponents: &[Opponent],
+    rounds: usize,
+    games: usize,
+    seed: u64,
+) {
+    let strategies = Strategy::all(rules);
+    let name_width = strategies
+        .iter()
+        .map(|s| s.name(rules).len())
+        .max()
+        .unwrap_or(0);
+    let col_width = opponents
+        .iter()
+        .map(|o| o.name().len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    println!("Mean score per round over {games} games of {rounds} rounds (seed {seed}):");
+    print!("{:name_width$}", "");
+    opponents
+        .iter()
+        .for_each(|o| print!("  {:>col_width$}", o.name()));
+    println!();
+
+    for strategy in strategies.iter() {
+        print!("{:name_width$}", strategy.name(rules));
+        for opponent in opponents.iter() {
+            let mean = mean_score(rules, opponent, strategy, rounds, games, seed);
+            print!("  {mean:>col_width$.3}");
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulate::*;
+
+    #[test]
+    fn same_seed_same_result() {
+        let rules = Rules::classic();
+        let opponent = Opponent::Fixed(vec![1.0, 2.0, 3.0]);
+
+        assert_eq!(
+            simulate(&rules, &opponent, &Strategy::Uniform, 1000, 7),
+            simulate(&rules, &opponent, &Strategy::Uniform, 1000, 7)
+        );
+    }
+
+    #[test]
+    fn always_paper_against_rock() {
+        let rules = Rules::classic();
+        let opponent = Opponent::Fixed(vec![1.0, 0.0, 0.0]);
+
+        assert_eq!(
+            simulate(&rules, &opponent, &Strategy::Always(Shape(1)), 100, 1),
+            8.0
+        );
+    }
+
+    #[test]
+    fn beat_counter_wins_against_counter() {
+        let rules = Rules::classic();
+        let mean = simulate(&rules, &Opponent::Counter, &Strategy::BeatCounter, 1000, 3);
+
+        // Only the first round is left to chance
+        assert!(mean > 6.0);
+    }
+
+    #[test]
+    fn repeat_column_is_not_one_draw() {
+        let rules = Rules::classic();
+        let mean = |shape| {
+            mean_score(
+                &rules,
+                &Opponent::Repeat,
+                &Strategy::Always(shape),
+                50,
+                300,
+                2022,
+            )
+        };
+
+        // Against a random but then fixed shape every constant strategy
+        // expects 3 result points on top of its shape
+        for shape in rules.all_shapes() {
+            let expected = 3.0 + get_shape_value(&rules, &shape) as f64;
+            assert!((mean(shape) - expected).abs() < 0.5, "{}", mean(shape));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// Step by step listing of both parts, to check the bitset logic by hand
+
+use crate::priority::PriorityScheme;
+use crate::{Group, Rucksack};
+use aoc::BitSet;
+
+// Lists the members of a set with their priorities, e.g. "p (16), L (38)".
+// Each priority is looked up again from the item itself, so the returned
+// total does not depend on how the set stores its members.
+fn list_items(scheme: PriorityScheme, types: &BitSet) -> (String, usize) {
+    let items: Vec<(char, usize)> = types
+        .iter()
+        .filter_map(|p| scheme.item(p))
+        .filter_map(|c| Some((c, scheme.priority(c)?)))
+        .collect();
+
+    if items.is_empty() {
+        return (String::from("none"), 0);
+    }
+
+    (
+        items
+            .iter()
+            .map(|(c, p)| format!("{c} ({p})"))
+            .collect::<Vec<String>>()
+            .join(", "),
+        items.iter().map(|(_, p)| p).sum(),
+    )
+}
+
+// Totals of the priorities as listed, for misplaced items and for badges
+fn listed_totals(
+    scheme: PriorityScheme,
+    rucksacks: &[Rucksack],
+    groups: &[Group],
+) -> (usize, usize) {
+    let misplaced = rucksacks
+        .iter()
+        .map(|rucksack| list_items(scheme, &rucksack.misplaced).1)
+        .sum();
+    let badges = groups
+        .iter()
+        .map(|group| list_items(scheme, &group.badge).1)
+        .sum();
+
+    (misplaced, badges)
+}
+
+fn reconcile(name: &str, listed: usize, total: usize) {
+    if listed == total {
+        println!("  {name}: listed {listed}, summed {total}, ok");
+    } else {
+        println!("  {name}: listed {listed}, summed {total}, MISMATCH");
+    }
+}
+
+pub fn print_explanation(
+    scheme: PriorityScheme,
+    rucksacks: &[Rucksack],
+    compartments: usize,
+    groups: &[Group],
+    (misplaced_total, badge_total): (usize, usize),
+) {
+    for rucksack in rucksacks.iter() {
+        let size = (rucksack.items.len() / compartments).max(1);
+        let parts: Vec<String> = rucksack
+            .items
+            .chunks(size)
+            .map(|part| part.iter().collect())
+            .collect();
+        let (items, _) = list_items(scheme, &rucksack.misplaced);
+
+        println!("Line {}: {}", rucksack.line, parts.join(" | "));
+        println!("  shared: {items}");
+    }
+
+    println!();
+    for group in groups.iter() {
+        let (items, _) = list_items(scheme, &group.badge);
+
+        println!(
+            "Group at lines {}: badge {items}",
+            group
+                .lines
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+
+    let (misplaced_listed, badge_listed) = listed_totals(scheme, rucksacks, groups);
+    println!();
+    println!("Reconciliation:");
+    reconcile("misplaced items", misplaced_listed, misplaced_total);
+    reconcile("badges", badge_listed, badge_total);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::explain::*;
+    use crate::{find_all_misplaced, find_badges, sum_priorities};
+
+    const TEST_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn listed_matches_summed() {
+        let scheme = PriorityScheme::Letters;
+        let (rucksacks, _) = find_all_misplaced(scheme, TEST_INPUT, 2);
+        let (groups, _) = find_badges(scheme, TEST_INPUT, 3);
+
+        let summed = (
+            rucksacks.iter().map(|r| sum_priorities(&r.misplaced)).sum(),
+            groups.iter().map(|g| sum_priorities(&g.badge)).sum(),
+        );
+
+        assert_eq!(listed_totals(scheme, &rucksacks, &groups), (157, 70));
+        assert_eq!(summed, (157, 70));
+    }
+
+    #[test]
+    fn lists_items_with_priorities() {
+        let scheme = PriorityScheme::Letters;
+        let (rucksacks, _) = find_all_misplaced(scheme, TEST_INPUT, 2);
+
+        assert_eq!(
+            list_items(scheme, &rucksacks[0].misplaced),
+            (String::from("p (16)"), 16)
+        );
+        assert_eq!(
+            list_items(scheme, &BitSet::new()),
+            (String::from("none"), 0)
+        );
+    }
+}
@@ -1,62 +1,401 @@
+mod explain;
+mod priority;
+mod regroup;
+mod repack;
 
-use aoc::get_file_content_or_exit;
+use std::fs;
 
-fn sum_priorities(types: u64) -> i32 {
-    let mut sum = 0;
-    for i in 1..=52 {
-        sum += ((types & (1 << i)) != 0) as i32 * i;
+use aoc::{get_file_content_or_exit, get_flag_value, get_flag_value_or_exit, has_flag, BitSet};
+use priority::PriorityScheme;
+
+// Every member of the set is a priority, so the sum is just the members
+fn sum_priorities(types: &BitSet) -> usize {
+    types.iter().sum()
+}
+
+fn get_priority(scheme: PriorityScheme, c: &char) -> Option<usize> {
+    scheme.priority(*c)
+}
+
+fn find_types(scheme: PriorityScheme, types: &str) -> BitSet {
+    types
+        .chars()
+        .filter_map(|c| get_priority(scheme, &c))
+        .collect()
+}
+
+fn format_types(scheme: PriorityScheme, types: &BitSet) -> String {
+    types.iter().filter_map(|p| scheme.item(p)).collect()
+}
+
+#[derive(PartialEq, Debug)]
+enum RucksackError {
+    UnevenLength(usize, usize),
+    InvalidItem(usize, char),
+}
+
+// Splits a rucksack into equally sized compartments and returns the item types
+// found in more than one of them
+fn find_misplaced(scheme: PriorityScheme, items: &[char], compartments: usize) -> BitSet {
+    let size = (items.len() / compartments).max(1);
+    let mut seen = BitSet::new();
+    let mut shared = BitSet::new();
+
+    for compartment in items.chunks(size) {
+        let types: BitSet = compartment
+            .iter()
+            .filter_map(|c| get_priority(scheme, c))
+            .collect();
+        shared = shared.union(&seen.intersection(&types));
+        seen = seen.union(&types);
     }
-    sum
+
+    shared
 }
 
-fn get_priority(c: &char) -> u8 {
-    match c {
-        'a'..='z' => *c as u8 - 'a' as u8 + 1,
-        'A'..='Z' => *c as u8 - 'A' as u8 + 27,
-        _ => 0,
+struct Rucksack {
+    line: usize,
+    items: Vec<char>,
+    misplaced: BitSet,
+}
+
+// Returns every valid rucksack with its misplaced items, and an error for each
+// line that cannot be split evenly or holds something that is not an item
+fn find_all_misplaced(
+    scheme: PriorityScheme,
+    input: &str,
+    compartments: usize,
+) -> (Vec<Rucksack>, Vec<RucksackError>) {
+    let mut misplaced = vec![];
+    let mut errors = vec![];
+
+    for (i, line) in input.lines().enumerate() {
+        let items: Vec<char> = line.trim().chars().collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        if let Some(c) = items.iter().find(|c| get_priority(scheme, c).is_none()) {
+            errors.push(RucksackError::InvalidItem(i + 1, *c));
+        } else if !items.len().is_multiple_of(compartments) {
+            errors.push(RucksackError::UnevenLength(i + 1, items.len()));
+        } else {
+            misplaced.push(Rucksack {
+                line: i + 1,
+                misplaced: find_misplaced(scheme, &items, compartments),
+                items,
+            });
+        }
     }
+
+    (misplaced, errors)
 }
 
-fn find_types(types: &str) -> u64 {
-    let mut counter: u64 = 0;
+fn describe_error(error: &RucksackError, compartments: usize) -> String {
+    match error {
+        RucksackError::UnevenLength(line, len) => {
+            format!(
+                "Line {line}: {len} items cannot be split into {compartments} equal compartments"
+            )
+        }
+        RucksackError::InvalidItem(line, c) => format!("Line {line}: '{c}' is not an item"),
+    }
+}
+
+struct Group {
+    lines: Vec<usize>,
+    badge: BitSet,
+}
+
+#[derive(PartialEq, Debug)]
+enum GroupIssue {
+    NoBadge(Vec<usize>),
+    Ambiguous(Vec<usize>, BitSet),
+    Incomplete(Vec<usize>),
+}
+
+// Splits the rucksacks into consecutive groups of `group_size`, skipping blank
+// lines. The common items of every complete group are returned, and a problem
+// is reported for each group that does not share exactly one item and for a
+// trailing partial group.
+fn find_badges(
+    scheme: PriorityScheme,
+    input: &str,
+    group_size: usize,
+) -> (Vec<Group>, Vec<GroupIssue>) {
+    let rucksacks: Vec<(usize, BitSet)> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, find_types(scheme, line.trim())))
+        .collect();
+
+    let mut groups = vec![];
+    let mut issues = vec![];
+
+    for chunk in rucksacks.chunks(group_size) {
+        let lines: Vec<usize> = chunk.iter().map(|(line, _)| *line).collect();
+
+        if chunk.len() < group_size {
+            issues.push(GroupIssue::Incomplete(lines));
+            continue;
+        }
+
+        let badge = chunk
+            .iter()
+            .skip(1)
+            .fold(chunk[0].1.clone(), |acc, (_, types)| {
+                acc.intersection(types)
+            });
+
+        match badge.len() {
+            0 => issues.push(GroupIssue::NoBadge(lines.clone())),
+            1 => {}
+            _ => issues.push(GroupIssue::Ambiguous(lines.clone(), badge.clone())),
+        }
+
+        groups.push(Group { lines, badge });
+    }
+
+    (groups, issues)
+}
 
-    types.chars().for_each(|c| {
-        let pri = get_priority(&c);
-        counter |= 1 << pri;
-    });
+fn describe_issue(scheme: PriorityScheme, issue: &GroupIssue, group_size: usize) -> String {
+    let list = |lines: &Vec<usize>| {
+        lines
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
 
-    counter
+    match issue {
+        GroupIssue::NoBadge(lines) => format!("Group at lines {} has no common item", list(lines)),
+        GroupIssue::Ambiguous(lines, badge) => format!(
+            "Group at lines {} shares several items: {}",
+            list(lines),
+            format_types(scheme, badge)
+        ),
+        GroupIssue::Incomplete(lines) => format!(
+            "Group at lines {} is incomplete ({} of {group_size} rucksacks)",
+            list(lines),
+            lines.len()
+        ),
+    }
 }
 
 fn main() {
     let data = get_file_content_or_exit();
+    let scheme_name = get_flag_value("--items").unwrap_or(String::from("letters"));
+    let Some(scheme) = PriorityScheme::parse(&scheme_name) else {
+        println!("Unknown item scheme: {scheme_name}");
+        return;
+    };
 
     // Part 1
-    let sum_types = data
-        .split('\n')
-        .map(|line| {
-            let comp_len = line.len() / 2;
-            let comp = (&line[..comp_len], &line[comp_len..]);
+    let compartments = get_flag_value_or_exit("--compartments", 2);
+    if compartments == 0 {
+        println!("Number of compartments must be positive");
+        return;
+    }
 
-            find_types(comp.0) & find_types(comp.1)
-        })
-        .fold(0, |acc, val| acc + sum_priorities(val));
+    let (misplaced, errors) = find_all_misplaced(scheme, &data, compartments);
+    errors
+        .iter()
+        .for_each(|err| eprintln!("{}", describe_error(err, compartments)));
+
+    let sum_types = misplaced
+        .iter()
+        .fold(0, |acc, rucksack| acc + sum_priorities(&rucksack.misplaced));
 
     println!("Sum of priorities: {}", sum_types);
 
     // Part 2
-    let mut badges: Vec<u64> = vec![];
-    data.split('\n').enumerate().for_each(|(i, line)| {
-        if badges.len() < (i / 3) + 1 {
-            badges.push(find_types(line));
+    let group_size = get_flag_value_or_exit("--group-size", 3);
+    if group_size == 0 {
+        println!("Group size must be positive");
+        return;
+    }
+
+    let (groups, issues) = find_badges(scheme, &data, group_size);
+    issues
+        .iter()
+        .for_each(|issue| eprintln!("{}", describe_issue(scheme, issue, group_size)));
+
+    let sum = groups
+        .iter()
+        .fold(0, |acc, group| acc + sum_priorities(&group.badge));
+
+    println!("Sum badge priorities: {}", sum);
+
+    if let Some(path) = get_flag_value("--repack") {
+        if compartments != 2 {
+            println!("Repacking only supports two compartments");
+            return;
         }
 
-        if let Some(t) = badges.get_mut(i / 3) {
-            *t &= find_types(line);
+        let (output, report) = repack::repack_input(scheme, &data);
+        if let Err(err) = fs::write(&path, output) {
+            println!("{path}: {err}");
+            return;
         }
-    });
 
-    let sum = badges.iter().fold(0, |acc, val| acc + sum_priorities(*val));
+        report
+            .skipped
+            .iter()
+            .for_each(|line| eprintln!("Line {line}: not a valid rucksack, copied as is"));
+        report
+            .unbalanced
+            .iter()
+            .for_each(|line| eprintln!("Line {line}: compartments cannot be made equal"));
 
-    println!("Sum badge priorities: {}", sum);
+        println!();
+        println!(
+            "Repacked {} rucksacks with {} moves into {path}",
+            report.changed, report.moves
+        );
+    }
+
+    if let Some(goal_name) = get_flag_value("--regroup") {
+        let Some(goal) = regroup::Goal::parse(&goal_name) else {
+            println!("Unknown regroup goal: {goal_name}");
+            return;
+        };
+        let node_limit = get_flag_value_or_exit("--regroup-limit", 1_000_000);
+
+        let rucksacks: Vec<(usize, BitSet)> = data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, find_types(scheme, line.trim())))
+            .collect();
+        let sets: Vec<BitSet> = rucksacks.iter().map(|(_, set)| set.clone()).collect();
+        let result = regroup::regroup(&sets, group_size, goal, node_limit);
+
+        println!();
+        match (result.best, result.exhausted) {
+            (None, true) => println!("No grouping gives every group exactly one badge"),
+            (None, false) => println!("No grouping found within {node_limit} steps"),
+            (Some(best), exhausted) => {
+                for (members, badge) in best.groups.iter() {
+                    let lines = members
+                        .iter()
+                        .map(|m| rucksacks[*m].0.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    let item = scheme.item(*badge).unwrap_or('?');
+                    println!("Lines {lines}: badge {item} ({badge})");
+                }
+
+                if exhausted {
+                    println!("Optimal regrouped badge priorities: {}", best.total);
+                } else {
+                    println!(
+                        "Best regrouped badge priorities within {node_limit} steps: {}",
+                        best.total
+                    );
+                }
+            }
+        }
+    }
+
+    if has_flag("--explain") {
+        println!();
+        explain::print_explanation(scheme, &misplaced, compartments, &groups, (sum_types, sum));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const TEST_INPUT: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn test_part_1() {
+        let (misplaced, errors) = find_all_misplaced(PriorityScheme::Letters, TEST_INPUT, 2);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            misplaced
+                .iter()
+                .map(|r| sum_priorities(&r.misplaced))
+                .sum::<usize>(),
+            157
+        );
+    }
+
+    #[test]
+    fn shared_between_any_compartments() {
+        let items: Vec<char> = "abcdeafghbij".chars().collect();
+
+        let scheme = PriorityScheme::Letters;
+
+        assert_eq!(find_misplaced(scheme, &items, 3), find_types(scheme, "ab"));
+        assert_eq!(find_misplaced(scheme, &items, 2), find_types(scheme, "b"));
+    }
+
+    #[test]
+    fn reports_rucksack_errors() {
+        let (misplaced, errors) =
+            find_all_misplaced(PriorityScheme::Letters, "abca\nabc\nab1a\n\nabcä", 2);
+
+        assert_eq!(misplaced.len(), 1);
+        assert_eq!(
+            errors,
+            [
+                RucksackError::UnevenLength(2, 3),
+                RucksackError::InvalidItem(3, '1'),
+                RucksackError::InvalidItem(5, 'ä'),
+            ]
+        );
+    }
+
+    #[test]
+    fn extended_items() {
+        let (misplaced, errors) = find_all_misplaced(PriorityScheme::Extended, "a1!é\né!1b", 2);
+
+        assert!(errors.is_empty());
+        assert_eq!(misplaced.len(), 2);
+        assert!(misplaced.iter().all(|r| r.misplaced.is_empty()));
+
+        let (misplaced, _) = find_all_misplaced(PriorityScheme::Extended, "1!é1", 2);
+        assert_eq!(sum_priorities(&misplaced[0].misplaced), 54);
+    }
+
+    #[test]
+    fn test_part_2() {
+        let (groups, issues) = find_badges(PriorityScheme::Letters, TEST_INPUT, 3);
+
+        assert!(issues.is_empty());
+        assert_eq!(
+            groups
+                .iter()
+                .map(|g| sum_priorities(&g.badge))
+                .sum::<usize>(),
+            70
+        );
+    }
+
+    #[test]
+    fn reports_group_issues() {
+        let (groups, issues) =
+            find_badges(PriorityScheme::Letters, "ab\nab\nac\nde\n\nef\nfg\nx", 2);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            issues,
+            [
+                GroupIssue::Ambiguous(vec![1, 2], find_types(PriorityScheme::Letters, "ab")),
+                GroupIssue::NoBadge(vec![3, 4]),
+                GroupIssue::Incomplete(vec![8]),
+            ]
+        );
+    }
 }
@@ -0,0 +1,91 @@
+// Mapping between item types and priorities. The puzzle only has letters, the
+// extended scheme gives every other Unicode scalar value a priority as well.
+
+#[derive(Clone, Copy)]
+pub enum PriorityScheme {
+    // a-z are 1-26 and A-Z are 27-52, anything else is not an item
+    Letters,
+    // Letters as above, digits 53-62, ASCII punctuation 63-94 in ASCII order
+    // and any other character 95 + its code point
+    Extended,
+}
+
+const DIGITS: usize = 53;
+const PUNCTUATION: usize = 63;
+const OTHER: usize = 95;
+
+fn punctuation() -> impl Iterator<Item = char> {
+    ('!'..='~').filter(|c| c.is_ascii_punctuation())
+}
+
+impl PriorityScheme {
+    pub fn parse(name: &str) -> Option<PriorityScheme> {
+        match name {
+            "letters" => Some(PriorityScheme::Letters),
+            "extended" => Some(PriorityScheme::Extended),
+            _ => None,
+        }
+    }
+
+    pub fn priority(&self, c: char) -> Option<usize> {
+        match (self, c) {
+            (_, 'a'..='z') => Some(c as usize - 'a' as usize + 1),
+            (_, 'A'..='Z') => Some(c as usize - 'A' as usize + 27),
+            (PriorityScheme::Letters, _) => None,
+            (PriorityScheme::Extended, '0'..='9') => Some(c as usize - '0' as usize + DIGITS),
+            (PriorityScheme::Extended, _) if c.is_ascii_punctuation() => {
+                punctuation().position(|p| p == c).map(|i| i + PUNCTUATION)
+            }
+            (PriorityScheme::Extended, _) => Some(c as usize + OTHER),
+        }
+    }
+
+    pub fn item(&self, priority: usize) -> Option<char> {
+        let c = match priority {
+            1..=26 => char::from_u32('a' as u32 + priority as u32 - 1),
+            27..=52 => char::from_u32('A' as u32 + priority as u32 - 27),
+            DIGITS..=62 => char::from_u32('0' as u32 + (priority - DIGITS) as u32),
+            PUNCTUATION..=94 => punctuation().nth(priority - PUNCTUATION),
+            _ => char::from_u32(priority.checked_sub(OTHER)? as u32),
+        }?;
+
+        // Rejects priorities the scheme never hands out
+        (self.priority(c) == Some(priority)).then_some(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::priority::*;
+
+    #[test]
+    fn letters_match_puzzle() {
+        let scheme = PriorityScheme::Letters;
+
+        assert_eq!(scheme.priority('a'), Some(1));
+        assert_eq!(scheme.priority('Z'), Some(52));
+        assert_eq!(scheme.priority('1'), None);
+        assert_eq!(scheme.item(53), None);
+    }
+
+    #[test]
+    fn extended_round_trips() {
+        let scheme = PriorityScheme::Extended;
+        let items = "aZ09!~/é€😀 ";
+
+        let priorities: Vec<usize> = items.chars().filter_map(|c| scheme.priority(c)).collect();
+        assert_eq!(priorities[..6], [1, 52, 53, 62, 63, 94]);
+
+        let mut sorted = priorities.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), items.chars().count());
+        assert_eq!(
+            priorities
+                .iter()
+                .filter_map(|p| scheme.item(*p))
+                .collect::<String>(),
+            items
+        );
+    }
+}
@@ -0,0 +1,293 @@
+// Search for a partition of all rucksacks into groups that each share exactly
+// one item, ignoring the order of the input
+
+use aoc::BitSet;
+
+use crate::sum_priorities;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Goal {
+    Min,
+    Max,
+}
+
+impl Goal {
+    pub fn parse(name: &str) -> Option<Goal> {
+        match name {
+            "min" => Some(Goal::Min),
+            "max" => Some(Goal::Max),
+            _ => None,
+        }
+    }
+
+    fn improves(&self, total: usize, best: usize) -> bool {
+        match self {
+            Goal::Min => total < best,
+            Goal::Max => total > best,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Regrouping {
+    // Rucksack indices of every group, with the priority of its badge
+    pub groups: Vec<(Vec<usize>, usize)>,
+    pub total: usize,
+}
+
+pub struct SearchResult {
+    pub best: Option<Regrouping>,
+    // False when the node limit stopped the search early, in which case the
+    // best partition is not proven optimal and a missing one not proven
+    // impossible
+    pub exhausted: bool,
+}
+
+struct Search<'a> {
+    sets: &'a [BitSet],
+    group_size: usize,
+    goal: Goal,
+    // Highest item priority of each rucksack for Max, lowest for Min. A
+    // badge can be no better than this for any of its group's members.
+    bounds: Vec<usize>,
+    assigned: Vec<bool>,
+    groups: Vec<(Vec<usize>, usize)>,
+    total: usize,
+    best: Option<Regrouping>,
+    nodes: usize,
+    node_limit: usize,
+}
+
+impl Search<'_> {
+    // Optimistic total of the rucksacks that are not grouped yet
+    fn remaining_bound(&self) -> usize {
+        let sum: usize = self
+            .bounds
+            .iter()
+            .zip(self.assigned.iter())
+            .filter(|(_, assigned)| !**assigned)
+            .map(|(b, _)| b)
+            .sum();
+
+        match self.goal {
+            Goal::Max => sum / self.group_size,
+            Goal::Min => sum.div_ceil(self.group_size),
+        }
+    }
+
+    fn can_improve(&self, total: usize) -> bool {
+        self.best
+            .as_ref()
+            .is_none_or(|best| self.goal.improves(total, best.total))
+    }
+
+    // Groups the lowest unassigned rucksack with every possible set of
+    // partners. Returns false once the node limit is hit.
+    fn search(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            return false;
+        }
+
+        let Some(anchor) = self.assigned.iter().position(|a| !a) else {
+            if self.can_improve(self.total) {
+                self.best = Some(Regrouping {
+                    groups: self.groups.clone(),
+                    total: self.total,
+                });
+            }
+            return true;
+        };
+
+        let bound = self.total + self.remaining_bound();
+        if !self.can_improve(bound) {
+            return true;
+        }
+
+        self.assigned[anchor] = true;
+        let common = self.sets[anchor].clone();
+        let completed = self.extend(&mut vec![anchor], &common, anchor + 1);
+        self.assigned[anchor] = false;
+
+        completed
+    }
+
+    fn extend(&mut self, members: &mut Vec<usize>, common: &BitSet, start: usize) -> bool {
+        if members.len() == self.group_size {
+            if common.len() != 1 {
+                return true;
+            }
+
+            let badge = sum_priorities(common);
+            self.total += badge;
+            self.groups.push((members.clone(), badge));
+            let completed = self.search();
+            self.groups.pop();
+            self.total -= badge;
+
+            return completed;
+        }
+
+        for next in start..self.sets.len() {
+            if self.assigned[next] {
+                continue;
+            }
+
+            let narrowed = common.intersection(&self.sets[next]);
+            if narrowed.is_empty() {
+                continue;
+            }
+
+            self.assigned[next] = true;
+            members.push(next);
+            let completed = self.extend(members, &narrowed, next + 1);
+            members.pop();
+            self.assigned[next] = false;
+
+            if !completed {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// Branch and bound over all partitions, visiting at most `node_limit` partial
+// partitions
+pub fn regroup(sets: &[BitSet], group_size: usize, goal: Goal, node_limit: usize) -> SearchResult {
+    if group_size == 0 || !sets.len().is_multiple_of(group_size) {
+        return SearchResult {
+            best: None,
+            exhausted: true,
+        };
+    }
+
+    let bounds = sets
+        .iter()
+        .map(|set| match goal {
+            Goal::Max => set.iter().last().unwrap_or(0),
+            Goal::Min => set.iter().next().unwrap_or(0),
+        })
+        .collect();
+
+    let mut search = Search {
+        sets,
+        group_size,
+        goal,
+        bounds,
+        assigned: vec![false; sets.len()],
+        groups: vec![],
+        total: 0,
+        best: None,
+        nodes: 0,
+        node_limit,
+    };
+    let exhausted = search.search();
+
+    SearchResult {
+        best: search.best,
+        exhausted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::find_types;
+    use crate::priority::PriorityScheme;
+    use crate::regroup::*;
+
+    fn sets(input: &str) -> Vec<BitSet> {
+        input
+            .lines()
+            .map(|line| find_types(PriorityScheme::Letters, line))
+            .collect()
+    }
+
+    // Every split of six rucksacks into two triples, kept when both triples
+    // share exactly one item
+    fn brute_force_pairs_of_triples(sets: &[BitSet]) -> Vec<Regrouping> {
+        let group = |members: Vec<usize>| {
+            let common = members
+                .iter()
+                .skip(1)
+                .fold(sets[members[0]].clone(), |common, m| {
+                    common.intersection(&sets[*m])
+                });
+            (common.len() == 1).then(|| (members, sum_priorities(&common)))
+        };
+
+        let mut partitions = vec![];
+        for i in 1..6 {
+            for j in i + 1..6 {
+                let rest: Vec<usize> = (1..6).filter(|m| *m != i && *m != j).collect();
+                if let (Some(first), Some(second)) = (group(vec![0, i, j]), group(rest)) {
+                    partitions.push(Regrouping {
+                        total: first.1 + second.1,
+                        groups: vec![first, second],
+                    });
+                }
+            }
+        }
+        partitions
+    }
+
+    #[test]
+    fn example_matches_brute_force() {
+        let sets = sets(
+            "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+        );
+        let partitions = brute_force_pairs_of_triples(&sets);
+        let brute_max = partitions.iter().max_by_key(|p| p.total).unwrap();
+        let brute_min = partitions.iter().min_by_key(|p| p.total).unwrap();
+
+        let max = regroup(&sets, 3, Goal::Max, 100_000);
+        let min = regroup(&sets, 3, Goal::Min, 100_000);
+        assert!(max.exhausted && min.exhausted);
+
+        // Only two of the ten splits give every triple a single badge, the
+        // input order being the better one
+        assert_eq!(partitions.len(), 2);
+        let (max, min) = (max.best.unwrap(), min.best.unwrap());
+        assert_eq!(
+            (max.total, &max.groups),
+            (brute_max.total, &brute_max.groups)
+        );
+        assert_eq!(
+            (min.total, &min.groups),
+            (brute_min.total, &brute_min.groups)
+        );
+        assert_eq!(max.groups, [(vec![0, 1, 2], 18), (vec![3, 4, 5], 52)]);
+        assert_eq!(min.groups, [(vec![0, 4, 5], 36), (vec![1, 2, 3], 17)]);
+        assert_eq!((max.total, min.total), (70, 53));
+    }
+
+    #[test]
+    fn finds_optimal_partition() {
+        // Either {ab, ac} + {xb, xc} sharing a and x, or {ab, xb} + {ac, xc}
+        // sharing b and c
+        let sets = sets("ab\nac\nxb\nxc");
+
+        let max = regroup(&sets, 2, Goal::Max, 1000).best.unwrap();
+        let min = regroup(&sets, 2, Goal::Min, 1000).best.unwrap();
+
+        assert_eq!(max.total, 1 + 24);
+        assert_eq!(max.groups[0].0, [0, 1]);
+        assert_eq!(min.total, 2 + 3);
+        assert_eq!(min.groups[0].0, [0, 2]);
+    }
+
+    #[test]
+    fn reports_impossible_partition() {
+        let result = regroup(&sets("ab\ncd\nef"), 3, Goal::Max, 1000);
+
+        assert!(result.exhausted);
+        assert!(result.best.is_none());
+        assert!(regroup(&sets("ab\nab"), 3, Goal::Max, 1000).best.is_none());
+    }
+}
@@ -0,0 +1,163 @@
+// Rewrites rucksacks so that no item type is split across both compartments
+
+use std::collections::BTreeMap;
+
+use crate::get_priority;
+use crate::priority::PriorityScheme;
+
+pub struct Repacked {
+    pub items: Vec<char>,
+    pub moves: usize,
+    // Whether both compartments ended up the same size
+    pub balanced: bool,
+}
+
+#[derive(Default)]
+pub struct RepackReport {
+    pub changed: usize,
+    pub moves: usize,
+    pub unbalanced: Vec<usize>,
+    pub skipped: Vec<usize>,
+}
+
+// Every item type has to end up entirely in one compartment. Moving a type to
+// the left costs its count on the right and vice versa, so this is a subset
+// sum over the type counts: find the assignment that makes the left
+// compartment as close to half the rucksack as possible, with the fewest
+// moves among those. Types that are not shared only move when that is needed
+// to keep the sizes equal.
+pub fn repack(scheme: PriorityScheme, items: &[char]) -> Repacked {
+    let half = items.len() / 2;
+    let (left, right) = items.split_at(half);
+
+    let mut counts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    left.iter()
+        .filter_map(|c| get_priority(scheme, c))
+        .for_each(|p| counts.entry(p).or_default().0 += 1);
+    right
+        .iter()
+        .filter_map(|c| get_priority(scheme, c))
+        .for_each(|p| counts.entry(p).or_default().1 += 1);
+
+    // best[size] is the cheapest assignment putting `size` items on the left,
+    // as (moves, types sent left)
+    let mut best: Vec<Option<(usize, Vec<usize>)>> = vec![None; items.len() + 1];
+    best[0] = Some((0, vec![]));
+
+    for (priority, (l, r)) in counts.iter() {
+        let mut next: Vec<Option<(usize, Vec<usize>)>> = vec![None; items.len() + 1];
+
+        for (size, entry) in best.iter().enumerate() {
+            let Some((moves, sent_left)) = entry else {
+                continue;
+            };
+
+            let mut keep = |size: usize, moves: usize, sent_left: Vec<usize>| {
+                if next[size].as_ref().is_none_or(|(m, _)| moves < *m) {
+                    next[size] = Some((moves, sent_left));
+                }
+            };
+
+            keep(size, moves + l, sent_left.clone());
+
+            let mut with = sent_left.clone();
+            with.push(*priority);
+            keep(size + l + r, moves + r, with);
+        }
+
+        best = next;
+    }
+
+    let (size, (moves, sent_left)) = best
+        .into_iter()
+        .enumerate()
+        .filter_map(|(size, entry)| Some((size, entry?)))
+        .min_by_key(|(size, (moves, _))| (size.abs_diff(half), *moves))
+        .expect("assigning every type right is always possible");
+
+    let goes_left =
+        |c: &&char| get_priority(scheme, c).is_none_or(|p| sent_left.binary_search(&p).is_ok());
+    let mut repacked: Vec<char> = left.iter().filter(goes_left).copied().collect();
+    repacked.extend(right.iter().filter(goes_left));
+    repacked.extend(left.iter().filter(|c| !goes_left(c)));
+    repacked.extend(right.iter().filter(|c| !goes_left(c)));
+
+    Repacked {
+        items: repacked,
+        moves,
+        balanced: size * 2 == items.len(),
+    }
+}
+
+// Repacks every valid rucksack in the input and copies all other lines as
+// they are
+pub fn repack_input(scheme: PriorityScheme, input: &str) -> (String, RepackReport) {
+    let mut output = String::with_capacity(input.len());
+    let mut report = RepackReport::default();
+
+    for (i, line) in input.lines().enumerate() {
+        let items: Vec<char> = line.trim().chars().collect();
+        let valid = items.iter().all(|c| get_priority(scheme, c).is_some());
+
+        if items.is_empty() || !valid {
+            if !items.is_empty() {
+                report.skipped.push(i + 1);
+            }
+            output.push_str(line);
+        } else {
+            let repacked = repack(scheme, &items);
+            if repacked.moves > 0 {
+                report.changed += 1;
+            }
+            if !repacked.balanced {
+                report.unbalanced.push(i + 1);
+            }
+            report.moves += repacked.moves;
+            output.extend(repacked.items.iter());
+        }
+        output.push('\n');
+    }
+
+    (output, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repack::*;
+    use crate::{find_all_misplaced, find_misplaced};
+
+    #[test]
+    fn moves_shared_items() {
+        let items: Vec<char> = "abcabd".chars().collect();
+        let repacked = repack(PriorityScheme::Letters, &items);
+
+        assert!(repacked.balanced);
+        assert_eq!(repacked.moves, 2);
+        assert!(find_misplaced(PriorityScheme::Letters, &repacked.items, 2).is_empty());
+    }
+
+    #[test]
+    fn repacked_input_scores_zero() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+        let (output, report) = repack_input(PriorityScheme::Letters, input);
+        let (rucksacks, errors) = find_all_misplaced(PriorityScheme::Letters, &output, 2);
+
+        assert!(errors.is_empty());
+        assert!(report.unbalanced.is_empty());
+        assert_eq!(report.changed, 6);
+        assert!(rucksacks.iter().all(|r| r.misplaced.is_empty()));
+    }
+
+    #[test]
+    fn reports_unbalanced() {
+        let items: Vec<char> = "aaab".chars().collect();
+
+        assert!(!repack(PriorityScheme::Letters, &items).balanced);
+    }
+}
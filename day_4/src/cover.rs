@@ -0,0 +1,128 @@
+// Smallest set of elves that still covers every assigned section
+
+use crate::overlaps::Elf;
+
+// Indices of the chosen elves, in order of their sections. Walks along the
+// sections from the left and, at the first section not covered yet, takes the
+// elf starting there or earlier that reaches furthest. For intervals this
+// greedy choice is optimal.
+pub fn minimal_cover(elves: &[Elf]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_unstable_by_key(|i| (elves[*i].range.start(), *i));
+
+    let mut chosen: Vec<usize> = vec![];
+    let mut next = 0;
+
+    while next < order.len() {
+        // First section that still needs an elf, skipping sections nobody has
+        let covered_to = chosen.last().map(|c| elves[*c].range.end());
+        let mut section = elves[order[next]].range.start();
+        if let Some(end) = covered_to {
+            match end.checked_add(1) {
+                Some(after) => section = section.max(after),
+                None => break,
+            }
+        }
+
+        let mut best: Option<usize> = None;
+        while next < order.len() && elves[order[next]].range.start() <= section {
+            let candidate = order[next];
+            if best.is_none_or(|b| elves[candidate].range.end() > elves[b].range.end()) {
+                best = Some(candidate);
+            }
+            next += 1;
+        }
+
+        if let Some(best) = best.filter(|b| elves[*b].range.end() >= section) {
+            chosen.push(best);
+        }
+    }
+
+    chosen
+}
+
+pub fn print_cover(elves: &[Elf]) {
+    let chosen = minimal_cover(elves);
+
+    for elf in chosen.iter().map(|i| &elves[*i]) {
+        println!(
+            "Line {} #{}: {}-{}",
+            elf.line,
+            elf.member,
+            elf.range.start(),
+            elf.range.end()
+        );
+    }
+    println!(
+        "{} of {} elves cover every section, {} are redundant",
+        chosen.len(),
+        elves.len(),
+        elves.len() - chosen.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::{IntervalSet, Rng};
+
+    use crate::cover::*;
+    use crate::overlaps::find_elves;
+    use crate::parse::{parse_groups, Reversed};
+
+    fn elves(input: &str) -> Vec<Elf> {
+        find_elves(&parse_groups(input, Reversed::Reject).0)
+    }
+
+    fn covered(elves: &[Elf], indices: impl Iterator<Item = usize>) -> IntervalSet<i64> {
+        indices.map(|i| elves[i].range).collect()
+    }
+
+    #[test]
+    fn example_input() {
+        let elves = elves("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8");
+        let chosen = minimal_cover(&elves);
+
+        // 2-8 and then 7-9
+        assert_eq!(chosen.len(), 2);
+        assert_eq!(
+            covered(&elves, chosen.into_iter()),
+            covered(&elves, 0..elves.len())
+        );
+    }
+
+    #[test]
+    fn skips_gaps() {
+        let elves = elves("1-2,5-6\n6-9,20-30\n1-1,25-31");
+
+        assert_eq!(minimal_cover(&elves).len(), 5);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = Rng::new(3);
+        let mut next = |below: usize| rng.below(below) as i64;
+
+        for _ in 0..50 {
+            let input: String = (0..5)
+                .map(|_| {
+                    let (a, b) = (next(30), next(30));
+                    format!("{a}-{},{b}-{}\n", a + next(8), b + next(8))
+                })
+                .collect();
+            let elves = elves(&input);
+            let all = covered(&elves, 0..elves.len());
+
+            let smallest = (0u32..1 << elves.len())
+                .filter(|mask| {
+                    covered(&elves, (0..elves.len()).filter(|i| mask & (1 << i) != 0)) == all
+                })
+                .map(|mask| mask.count_ones() as usize)
+                .min()
+                .unwrap();
+
+            let chosen = minimal_cover(&elves);
+            assert_eq!(covered(&elves, chosen.iter().copied()), all);
+            assert_eq!(chosen.len(), smallest, "{input}");
+        }
+    }
+}
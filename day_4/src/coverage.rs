@@ -0,0 +1,114 @@
+// Sections covered by all assignments together, regardless of pairing
+
+use aoc::IntervalSet;
+
+use crate::Range;
+
+pub struct Coverage {
+    pub covered: IntervalSet<i64>,
+    // Most elves assigned to one section, with the first run of sections
+    // where that many overlap
+    pub busiest: Option<(usize, Range)>,
+}
+
+// Sweeps over the positions where ranges start or stop. A range stops at the
+// section after its end, so every event at one position is applied before
+// looking at the number of elves there.
+fn find_busiest(ranges: &[Range]) -> Option<(usize, Range)> {
+    let mut events: Vec<(i128, isize)> = ranges
+        .iter()
+        .flat_map(|range| [(range.start() as i128, 1), (range.end() as i128 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut depth = 0;
+    let mut most = 0;
+    let mut start = 0;
+    let mut end = None;
+
+    for (i, (position, change)) in events.iter().enumerate() {
+        depth += change;
+        if events.get(i + 1).is_some_and(|(next, _)| next == position) {
+            continue;
+        }
+
+        if depth > most {
+            most = depth;
+            start = *position;
+            end = None;
+        } else if depth < most && end.is_none() {
+            end = Some(position - 1);
+        }
+    }
+
+    let range = Range::inclusive(start as i64, end? as i64)?;
+    Some((most as usize, range))
+}
+
+pub fn find_coverage(ranges: &[Range]) -> Coverage {
+    Coverage {
+        covered: ranges.iter().copied().collect(),
+        busiest: find_busiest(ranges),
+    }
+}
+
+pub fn print_coverage(coverage: &Coverage) {
+    println!("Sections covered: {}", coverage.covered.len());
+
+    let gaps: Vec<String> = coverage
+        .covered
+        .gaps()
+        .map(|gap| format!("{}-{}", gap.start(), gap.end()))
+        .collect();
+    if gaps.is_empty() {
+        println!("No gaps");
+    } else {
+        println!("Gaps: {}", gaps.join(", "));
+    }
+
+    if let Some((most, sections)) = coverage.busiest {
+        println!(
+            "Most elves on one section: {most} (sections {}-{})",
+            sections.start(),
+            sections.end()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coverage::*;
+
+    fn ranges(list: &[(i64, i64)]) -> Vec<Range> {
+        list.iter()
+            .filter_map(|(start, end)| Range::inclusive(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn covered_sections_and_gaps() {
+        let coverage = find_coverage(&ranges(&[(2, 4), (6, 8), (3, 5), (10, 12)]));
+
+        assert_eq!(coverage.covered.len(), 10);
+        assert_eq!(
+            coverage.covered.gaps().collect::<Vec<Range>>(),
+            ranges(&[(9, 9)])
+        );
+    }
+
+    #[test]
+    fn busiest_sections() {
+        let list = [(2, 4), (6, 8), (2, 3), (4, 5), (3, 9), (7, 7)];
+        let coverage = find_coverage(&ranges(&list));
+
+        // Brute force over every section
+        let most = (0..12)
+            .map(|s| list.iter().filter(|(a, b)| *a <= s && s <= *b).count())
+            .max()
+            .unwrap();
+
+        assert_eq!(most, 3);
+        assert_eq!(coverage.busiest, Some((3, ranges(&[(3, 4)])[0])));
+        assert_eq!(find_coverage(&[]).busiest, None);
+    }
+}
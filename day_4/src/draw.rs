@@ -0,0 +1,213 @@
+// Section diagrams like the ones in the puzzle statement, one row per elf
+
+use crate::relation::Relation;
+use crate::{Group, Range};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cell {
+    Empty,
+    Assigned(char),
+    // Assigned to this elf and at least one other member of the group
+    Shared(char),
+}
+
+pub struct Diagram {
+    pub span: Range,
+    pub sections_per_column: u64,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+// Like in the puzzle statement the diagram starts at section `from` and runs
+// to the next section ending in 9, as long as that fits in `width` columns.
+// Otherwise it covers just the group's own sections, and when even those do
+// not fit, every column stands for several sections and is marked with '#'
+// instead of the last digit of the section.
+pub fn draw_group(group: &Group, width: usize, from: i64) -> Option<Diagram> {
+    let lowest = group.members.iter().map(|m| m.start()).min()?;
+    let highest = group.members.iter().map(|m| m.end()).max()?;
+    let padded_end = highest
+        .checked_add(9 - highest.rem_euclid(10))
+        .unwrap_or(highest);
+
+    let span = match Range::inclusive(lowest.min(from), padded_end) {
+        Some(padded) if padded.len() <= width as u64 => padded,
+        _ => Range::inclusive(lowest, highest)?,
+    };
+    let (start, end) = (span.start(), span.end());
+
+    let scale = span.len().div_ceil(width.max(1) as u64);
+    let columns = span.len().div_ceil(scale);
+    let buckets: Vec<Range> = (0..columns as i128)
+        .filter_map(|c| {
+            let first = start as i128 + c * scale as i128;
+            let last = (first + scale as i128 - 1).min(end as i128);
+            Range::inclusive(first as i64, last as i64)
+        })
+        .collect();
+
+    let rows = group
+        .members
+        .iter()
+        .enumerate()
+        .map(|(i, member)| {
+            let shared: Vec<Range> = group
+                .members
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .filter_map(|(_, other)| member.intersection(other))
+                .collect();
+
+            buckets
+                .iter()
+                .map(|bucket| {
+                    let mark = if scale == 1 {
+                        char::from_digit(bucket.start().rem_euclid(10) as u32, 10).unwrap()
+                    } else {
+                        '#'
+                    };
+
+                    if shared.iter().any(|s| s.overlaps(bucket)) {
+                        Cell::Shared(mark)
+                    } else if member.overlaps(bucket) {
+                        Cell::Assigned(mark)
+                    } else {
+                        Cell::Empty
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(Diagram {
+        span,
+        sections_per_column: scale,
+        rows,
+    })
+}
+
+fn render(row: &[Cell], colour: bool) -> String {
+    row.iter()
+        .map(|cell| match cell {
+            Cell::Empty => String::from("."),
+            Cell::Assigned(c) => c.to_string(),
+            Cell::Shared(c) if colour => format!("\x1b[1;33m{c}\x1b[0m"),
+            Cell::Shared(c) => c.to_string(),
+        })
+        .collect()
+}
+
+// Shared sections are highlighted with ANSI colours when `colour` is set
+pub fn print_diagrams(
+    groups: &[Group],
+    width: usize,
+    from: i64,
+    lines: Option<&[usize]>,
+    colour: bool,
+) {
+    for group in groups.iter() {
+        if lines.is_some_and(|lines| !lines.contains(&group.line)) {
+            continue;
+        }
+        let Some(diagram) = draw_group(group, width, from) else {
+            continue;
+        };
+
+        let relation = match group.members[..] {
+            [left, right] => format!(", {}", Relation::classify(&left, &right).name()),
+            _ => String::new(),
+        };
+        let scale = match diagram.sections_per_column {
+            1 => String::new(),
+            n => format!(", {n} sections per column"),
+        };
+        println!(
+            "Line {}: {}-{}{scale}{relation}",
+            group.line,
+            diagram.span.start(),
+            diagram.span.end()
+        );
+
+        let labels: Vec<String> = group
+            .members
+            .iter()
+            .map(|m| format!("{}-{}", m.start(), m.end()))
+            .collect();
+        let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+
+        for (label, row) in labels.iter().zip(diagram.rows.iter()) {
+            println!("  {label:>label_width$}  {}", render(row, colour));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::*;
+    use crate::parse::{parse_groups, Reversed};
+
+    fn rows(input: &str, width: usize) -> Vec<String> {
+        let (groups, _) = parse_groups(input, Reversed::Reject);
+        let diagram = draw_group(&groups[0], width, 1).unwrap();
+
+        diagram.rows.iter().map(|row| render(row, false)).collect()
+    }
+
+    #[test]
+    fn puzzle_style() {
+        assert_eq!(rows("2-4,6-8", 80), [".234.....", ".....678."]);
+        assert_eq!(
+            rows("5-7,7-9,12-13", 80),
+            [
+                "....567............",
+                "......789..........",
+                "...........23......"
+            ]
+        );
+    }
+
+    #[test]
+    fn own_sections_when_the_layout_does_not_fit() {
+        assert_eq!(rows("2-4,6-8", 8), ["234....", "....678"]);
+        assert_eq!(rows("1000-1002,1001-1003", 80), ["012.", ".123"]);
+    }
+
+    #[test]
+    fn puzzle_ids_fit_the_default_width() {
+        let (groups, _) = parse_groups("1-99,50-50", Reversed::Reject);
+        let diagram = draw_group(&groups[0], 100, 1).unwrap();
+
+        assert_eq!(diagram.sections_per_column, 1);
+        assert_eq!(diagram.rows[0].len(), 99);
+    }
+
+    #[test]
+    fn highlights_shared_sections() {
+        let (groups, _) = parse_groups("5-7,7-9", Reversed::Reject);
+        let diagram = draw_group(&groups[0], 80, 5).unwrap();
+
+        assert_eq!(render(&diagram.rows[1], true), "..\x1b[1;33m7\x1b[0m89");
+
+        assert_eq!(
+            diagram.rows[0],
+            [
+                Cell::Assigned('5'),
+                Cell::Assigned('6'),
+                Cell::Shared('7'),
+                Cell::Empty,
+                Cell::Empty
+            ]
+        );
+    }
+
+    #[test]
+    fn scales_wide_ranges() {
+        let (groups, _) = parse_groups("1-1000,990-1000", Reversed::Reject);
+        let diagram = draw_group(&groups[0], 10, 1).unwrap();
+
+        assert_eq!(diagram.sections_per_column, 100);
+        assert_eq!(render(&diagram.rows[0], false), "##########");
+        assert_eq!(render(&diagram.rows[1], false), ".........#");
+        assert_eq!(diagram.rows[0][9], Cell::Shared('#'));
+    }
+}
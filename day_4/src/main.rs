@@ -1,98 +1,240 @@
-use aoc::get_file_content_or_exit;
-use std::cmp;
+mod cover;
+mod coverage;
+mod draw;
+mod overlaps;
+mod parse;
+mod relation;
 
-#[derive(Clone, Copy)]
-struct Range {
-    start: i32,
-    end: i32,
-}
+use std::io::IsTerminal;
 
-struct Pair {
-    left: Range,
-    right: Range,
-}
+use aoc::{get_file_content_or_exit, get_flag_value, get_flag_value_or_exit, has_flag, Interval};
+use parse::{parse_groups, Reversed};
+use relation::Relation;
 
-enum Intersection {
-    Disjoint,
-    Partial(i32, i32),
-    Contain(i32, i32),
+type Range = Interval<i64>;
+
+// The elves listed on one line, usually two
+struct Group {
+    line: usize,
+    members: Vec<Range>,
 }
 
-fn compute_intersection(pair: &Pair) -> Intersection {
-    if pair.left.start < pair.right.start {
-        if pair.left.end < pair.right.start {
-            Intersection::Disjoint
-        } else if pair.left.end >= pair.right.end {
-            Intersection::Contain(pair.right.start, pair.right.end)
-        } else {
-            Intersection::Partial(pair.right.start, pair.left.end)
+impl Group {
+    // Relation of every member to each member after it
+    fn relations(&self) -> impl Iterator<Item = (usize, usize, Relation)> + '_ {
+        self.members.iter().enumerate().flat_map(move |(i, a)| {
+            self.members
+                .iter()
+                .enumerate()
+                .skip(i + 1)
+                .map(move |(j, b)| (i, j, Relation::classify(a, b)))
+        })
+    }
+
+    fn has_containment(&self) -> bool {
+        self.relations().any(|(_, _, r)| r.is_containment())
+    }
+
+    fn has_overlap(&self) -> bool {
+        self.relations().any(|(_, _, r)| r.is_overlap())
+    }
+
+    // Sections assigned to every member
+    fn common(&self) -> Option<Range> {
+        let (first, rest) = self.members.split_first()?;
+        rest.iter()
+            .try_fold(*first, |common, member| common.intersection(member))
+    }
+
+    // Members whose sections all belong to another member too
+    fn contained(&self) -> Vec<usize> {
+        let mut inside = vec![false; self.members.len()];
+        for (i, j, relation) in self.relations() {
+            inside[i] |= relation.is_inside();
+            inside[j] |= relation.is_containing();
         }
-    } else if pair.right.start < pair.left.start {
-        if pair.right.end < pair.left.start {
-            Intersection::Disjoint
-        } else if pair.right.end >= pair.left.end {
-            Intersection::Contain(pair.left.start, pair.left.end)
-        } else {
-            Intersection::Partial(pair.left.start, pair.right.end)
+
+        (0..inside.len()).filter(|i| inside[*i]).collect()
+    }
+
+    // Number of other members each member shares a section with
+    fn overlap_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.members.len()];
+        for (i, j, relation) in self.relations() {
+            if relation.is_overlap() {
+                counts[i] += 1;
+                counts[j] += 1;
+            }
         }
-    } else {
-        Intersection::Contain(pair.left.start, cmp::min(pair.left.end, pair.right.end))
+        counts
+    }
+}
+
+// Number of member pairs in each relation, indexed like Relation::ALL
+fn count_relations(groups: &[Group]) -> [usize; 13] {
+    let mut counts = [0; 13];
+    groups
+        .iter()
+        .flat_map(Group::relations)
+        .for_each(|(_, _, relation)| counts[relation.index()] += 1);
+    counts
+}
+
+fn print_groups(groups: &[Group]) {
+    for group in groups.iter() {
+        let list = |members: Vec<usize>| {
+            members
+                .iter()
+                .map(|m| format!("#{}", m + 1))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let common = group
+            .common()
+            .map(|c| format!("{}-{}", c.start(), c.end()))
+            .unwrap_or(String::from("none"));
+        let counts = group
+            .overlap_counts()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        println!(
+            "Line {}: {} elves, common {common}, contained [{}], overlaps [{counts}]",
+            group.line,
+            group.members.len(),
+            list(group.contained())
+        );
     }
 }
 
 fn main() {
     let data = get_file_content_or_exit();
-    let cleaned = data.replace('\r', "");
-    let pairs = cleaned.split('\n').map(|line| {
-        if line.len() == 0 {
-            return None;
-        }
-        let pair: Vec<Range> = line
-            .split(',')
-            .map(|range| {
-                let mut sides = range.split('-').map(|num| num.parse::<i32>());
-                let start = sides.next().unwrap_or(Ok(0)).unwrap_or(0);
-                let end = sides.next().unwrap_or(Ok(0)).unwrap_or(0);
-
-                Range { start, end }
-            })
-            .collect();
+    let reversed_name = get_flag_value("--reversed").unwrap_or(String::from("reject"));
+    let Some(reversed) = Reversed::parse(&reversed_name) else {
+        println!("Unknown handling of reversed ranges: {reversed_name}");
+        return;
+    };
 
-        Some(Pair {
-            left: pair.get(0).unwrap().to_owned(),
-            right: pair.get(1).unwrap().to_owned(),
-        })
-    });
+    let (groups, errors) = parse_groups(&data, reversed);
+    errors.iter().for_each(|err| eprintln!("Skipped {err}"));
 
     // Part 1
-    let total_full_intersections = pairs
-        .clone()
-        .filter(|p| {
-            if let Some(pair) = p {
-                if let Intersection::Contain(..) = compute_intersection(pair) {
-                    return true;
-                }
-            }
-            false
-        })
-        .count();
+    let total_full_intersections = groups.iter().filter(|g| g.has_containment()).count();
 
     println!("Total complete intersections: {}", total_full_intersections);
 
     // Part 2
-    let total_intersections = pairs
-        .clone()
-        .filter(|p| {
-            if let Some(pair) = p {
-                match compute_intersection(pair) {
-                    Intersection::Contain(..) | Intersection::Partial(..) => true,
-                    _ => false,
-                }
-            } else {
-                false
-            }
-        })
-        .count();
+    let total_intersections = groups.iter().filter(|g| g.has_overlap()).count();
 
     println!("Total intersections: {}", total_intersections);
+
+    if has_flag("--coverage") {
+        let ranges: Vec<Range> = groups
+            .iter()
+            .flat_map(|group| group.members.iter().copied())
+            .collect();
+
+        println!();
+        coverage::print_coverage(&coverage::find_coverage(&ranges));
+    }
+
+    if has_flag("--cover") {
+        println!();
+        cover::print_cover(&overlaps::find_elves(&groups));
+    }
+
+    if has_flag("--overlaps") {
+        println!();
+        overlaps::print_overlaps(&overlaps::find_elves(&groups));
+    }
+
+    if has_flag("--groups") {
+        println!();
+        print_groups(&groups);
+    }
+
+    if has_flag("--draw") {
+        let width = get_flag_value_or_exit("--draw-width", 100);
+        let from = get_flag_value_or_exit("--draw-from", 1);
+        let colour = !has_flag("--no-colour") && std::io::stdout().is_terminal();
+        let lines = match get_flag_value("--draw-lines") {
+            Some(list) => match list
+                .split(',')
+                .map(|l| l.trim().parse::<usize>())
+                .collect::<Result<Vec<usize>, _>>()
+            {
+                Ok(lines) => Some(lines),
+                Err(_) => {
+                    println!("Invalid line list: {list}");
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        println!();
+        draw::print_diagrams(&groups, width, from, lines.as_deref(), colour);
+    }
+
+    if has_flag("--relations") {
+        let counts = count_relations(&groups);
+
+        println!();
+        for (relation, count) in Relation::ALL.iter().zip(counts.iter()) {
+            println!("{:>13}: {count}", relation.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const TEST_INPUT: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn test_parts() {
+        let groups = parse_groups(TEST_INPUT, Reversed::Reject).0;
+
+        assert_eq!(groups.iter().filter(|g| g.has_containment()).count(), 2);
+        assert_eq!(groups.iter().filter(|g| g.has_overlap()).count(), 4);
+    }
+
+    #[test]
+    fn counts_relations() {
+        let counts = count_relations(&parse_groups(TEST_INPUT, Reversed::Reject).0);
+
+        assert_eq!(counts[Relation::Before.index()], 1);
+        assert_eq!(counts[Relation::Meets.index()], 1);
+        assert_eq!(counts[Relation::Overlaps.index()], 2);
+        assert_eq!(counts[Relation::Contains.index()], 1);
+        assert_eq!(counts[Relation::Finishes.index()], 1);
+        assert_eq!(counts.iter().sum::<usize>(), 6);
+    }
+
+    #[test]
+    fn larger_groups() {
+        let (groups, _) = parse_groups("2-8,3-5,4-9,4-4\n1-3\n1-2,5-6,2-5", Reversed::Reject);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].common(), Range::inclusive(4, 4));
+        assert_eq!(groups[0].contained(), [1, 3]);
+        assert_eq!(groups[0].overlap_counts(), [3, 3, 3, 3]);
+
+        assert_eq!(groups[1].common(), Range::inclusive(1, 3));
+        assert!(!groups[1].has_overlap());
+
+        assert_eq!(groups[2].common(), None);
+        assert_eq!(groups[2].contained(), []);
+        assert_eq!(groups[2].overlap_counts(), [1, 1, 2]);
+        assert_eq!(count_relations(&groups).iter().sum::<usize>(), 6 + 3);
+    }
 }
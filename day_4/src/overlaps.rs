@@ -0,0 +1,143 @@
+// Overlapping assignments across the whole file, not just within a line
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, BufWriter, Write};
+
+use crate::relation::Relation;
+use crate::{Group, Range};
+
+pub struct Elf {
+    pub line: usize,
+    // Position of the range within its line, starting at 1
+    pub member: usize,
+    pub range: Range,
+}
+
+pub fn find_elves(groups: &[Group]) -> Vec<Elf> {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group.members.iter().enumerate().map(|(i, range)| Elf {
+                line: group.line,
+                member: i + 1,
+                range: *range,
+            })
+        })
+        .collect()
+}
+
+// Calls `visit` once for every two elves sharing a section, the one starting
+// first (or on an earlier line) first. The elves are swept in order of their
+// start while the ones still active are kept in a heap by their end, so this
+// takes O(n log n + k) for k overlapping pairs.
+pub fn for_each_overlap(elves: &[Elf], mut visit: impl FnMut(&Elf, &Elf)) {
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_unstable_by_key(|i| (elves[*i].range.start(), *i));
+
+    let mut active: BinaryHeap<Reverse<(i64, usize)>> = BinaryHeap::new();
+
+    for i in order {
+        let elf = &elves[i];
+        while active
+            .peek()
+            .is_some_and(|Reverse((end, _))| *end < elf.range.start())
+        {
+            active.pop();
+        }
+
+        active
+            .iter()
+            .for_each(|Reverse((_, other))| visit(&elves[*other], elf));
+        active.push(Reverse((elf.range.end(), i)));
+    }
+}
+
+pub fn print_overlaps(elves: &[Elf]) {
+    let describe = |elf: &Elf| {
+        format!(
+            "line {} #{} ({}-{})",
+            elf.line,
+            elf.member,
+            elf.range.start(),
+            elf.range.end()
+        )
+    };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut count = 0;
+
+    for_each_overlap(elves, |a, b| {
+        count += 1;
+        // Output errors such as a closed pipe just cut the listing short
+        let _ = writeln!(
+            out,
+            "{} {} {}",
+            describe(a),
+            Relation::classify(&a.range, &b.range).name(),
+            describe(b)
+        );
+    });
+
+    let _ = writeln!(out, "Overlapping pairs: {count}");
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Rng;
+
+    use crate::overlaps::*;
+    use crate::parse::{parse_groups, Reversed};
+
+    fn brute_force(elves: &[Elf]) -> Vec<(usize, usize)> {
+        let mut pairs = vec![];
+        for (i, a) in elves.iter().enumerate() {
+            for (j, b) in elves.iter().enumerate().skip(i + 1) {
+                if a.range.overlaps(&b.range) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    fn sweep(elves: &[Elf]) -> Vec<(usize, usize)> {
+        let index = |elf: &Elf| (elf.line - 1) * 2 + elf.member - 1;
+        let mut pairs = vec![];
+        for_each_overlap(elves, |a, b| {
+            let (i, j) = (index(a), index(b));
+            pairs.push((i.min(j), i.max(j)));
+        });
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn example_input() {
+        let (groups, _) = parse_groups(
+            "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8",
+            Reversed::Reject,
+        );
+        let elves = find_elves(&groups);
+
+        assert_eq!(sweep(&elves), brute_force(&elves));
+        assert_eq!(sweep(&elves).len(), 49);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = Rng::new(11);
+        let mut next = |below: usize| rng.below(below) as i64;
+
+        let input: String = (0..300)
+            .map(|_| {
+                let (a, b) = (next(500) - 100, next(500) - 100);
+                format!("{a}-{},{b}-{}\n", a + next(20), b + next(20))
+            })
+            .collect();
+        let elves = find_elves(&parse_groups(&input, Reversed::Reject).0);
+
+        assert_eq!(elves.len(), 600);
+        assert_eq!(sweep(&elves), brute_force(&elves));
+    }
+}
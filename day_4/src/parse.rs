@@ -0,0 +1,218 @@
+// Strict parser for the assignment list. Every line holds comma separated
+// ranges like `2-4`, section IDs may be negative (`-5--3`) and whitespace
+// around numbers and separators is allowed.
+
+use std::fmt;
+
+use crate::{Group, Range};
+
+#[derive(Clone, Copy)]
+pub enum Reversed {
+    // Lines with a range like `6-4` are reported and skipped
+    Reject,
+    // `6-4` is read as `4-6`
+    Normalise,
+}
+
+impl Reversed {
+    pub fn parse(name: &str) -> Option<Reversed> {
+        match name {
+            "reject" => Some(Reversed::Reject),
+            "normalise" | "normalize" => Some(Reversed::Normalise),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    // 1-based position of the offending character
+    pub column: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+struct Cursor {
+    line: usize,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, pos: usize, reason: String) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: pos + 1,
+            reason,
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{c}'"),
+            None => String::from("end of line"),
+        }
+    }
+
+    fn expect(&mut self, expected: char, what: &str) -> Result<(), ParseError> {
+        self.skip_spaces();
+        if self.peek() != Some(expected) {
+            return Err(self.error(self.pos, format!("expected {what}, found {}", self.found())));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<i64, ParseError> {
+        self.skip_spaces();
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        let digits = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        if self.pos == digits {
+            return Err(self.error(
+                digits,
+                format!("expected a section ID, found {}", self.found()),
+            ));
+        }
+
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<i64>()
+            .map_err(|_| self.error(start, format!("section ID {text} is out of range")))
+    }
+
+    fn range(&mut self, reversed: Reversed) -> Result<Range, ParseError> {
+        self.skip_spaces();
+        let start_pos = self.pos;
+
+        let start = self.number()?;
+        self.expect('-', "'-' between the ends of a range")?;
+        let end = self.number()?;
+
+        match (Range::inclusive(start, end), reversed) {
+            (Some(range), _) => Ok(range),
+            (None, Reversed::Normalise) => Ok(Range::inclusive(end, start).unwrap()),
+            (None, Reversed::Reject) => Err(self.error(
+                start_pos,
+                format!("range {start}-{end} ends before it starts"),
+            )),
+        }
+    }
+}
+
+fn parse_line(line: usize, text: &str, reversed: Reversed) -> Result<Vec<Range>, ParseError> {
+    let mut cursor = Cursor {
+        line,
+        chars: text.chars().collect(),
+        pos: 0,
+    };
+
+    let mut members = vec![cursor.range(reversed)?];
+    loop {
+        cursor.skip_spaces();
+        if cursor.peek().is_none() {
+            return Ok(members);
+        }
+        cursor.expect(',', "',' between ranges")?;
+        members.push(cursor.range(reversed)?);
+    }
+}
+
+// Returns a group for every valid line and an error for every other non-blank
+// line
+pub fn parse_groups(input: &str, reversed: Reversed) -> (Vec<Group>, Vec<ParseError>) {
+    let mut groups = vec![];
+    let mut errors = vec![];
+
+    for (i, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        match parse_line(i + 1, text, reversed) {
+            Ok(members) => groups.push(Group {
+                line: i + 1,
+                members,
+            }),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (groups, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::*;
+
+    fn ranges(text: &str, reversed: Reversed) -> Result<Vec<(i64, i64)>, ParseError> {
+        parse_line(1, text, reversed)
+            .map(|members| members.iter().map(|r| (r.start(), r.end())).collect())
+    }
+
+    #[test]
+    fn negative_and_large_ids() {
+        assert_eq!(
+            ranges(" -5--3 , -1-2,3-3\r", Reversed::Reject),
+            Ok(vec![(-5, -3), (-1, 2), (3, 3)])
+        );
+        assert_eq!(
+            ranges("9000000000-9223372036854775807", Reversed::Reject),
+            Ok(vec![(9_000_000_000, i64::MAX)])
+        );
+    }
+
+    #[test]
+    fn reversed_ranges() {
+        assert_eq!(
+            ranges("2-4,8-6", Reversed::Normalise),
+            Ok(vec![(2, 4), (6, 8)])
+        );
+
+        let err = ranges("2-4, 8-6", Reversed::Reject).unwrap_err();
+        assert_eq!(err.column, 6);
+        assert_eq!(err.reason, "range 8-6 ends before it starts");
+    }
+
+    #[test]
+    fn reports_position() {
+        let (groups, errors) = parse_groups(
+            "2-4,6-8\n2-x,4-5\n\n2-3 4-5\n1-99999999999999999999\n2-4,",
+            Reversed::Reject,
+        );
+        let positions: Vec<(usize, usize)> = errors.iter().map(|e| (e.line, e.column)).collect();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(positions, [(2, 3), (4, 5), (5, 3), (6, 5)]);
+        assert_eq!(
+            errors[0].to_string(),
+            "line 2, column 3: expected a section ID, found 'x'"
+        );
+        assert_eq!(errors[3].reason, "expected a section ID, found end of line");
+    }
+}
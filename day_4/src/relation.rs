@@ -0,0 +1,192 @@
+// Allen's interval algebra. Every section is treated as a unit of length one,
+// so ranges that share a section overlap, and ranges where one ends right
+// before the other starts meet.
+
+use std::cmp::Ordering;
+
+use crate::Range;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 13] = [
+        Relation::Before,
+        Relation::Meets,
+        Relation::Overlaps,
+        Relation::Starts,
+        Relation::During,
+        Relation::Finishes,
+        Relation::Equals,
+        Relation::FinishedBy,
+        Relation::Contains,
+        Relation::StartedBy,
+        Relation::OverlappedBy,
+        Relation::MetBy,
+        Relation::After,
+    ];
+
+    // Relation of the left range to the right one
+    pub fn classify(left: &Range, right: &Range) -> Relation {
+        let (ls, le, rs, re) = (left.start(), left.end(), right.start(), right.end());
+
+        if le < rs {
+            return if left.is_adjacent(right) {
+                Relation::Meets
+            } else {
+                Relation::Before
+            };
+        }
+        if re < ls {
+            return if left.is_adjacent(right) {
+                Relation::MetBy
+            } else {
+                Relation::After
+            };
+        }
+
+        match (ls.cmp(&rs), le.cmp(&re)) {
+            (Ordering::Equal, Ordering::Equal) => Relation::Equals,
+            (Ordering::Equal, Ordering::Less) => Relation::Starts,
+            (Ordering::Equal, Ordering::Greater) => Relation::StartedBy,
+            (Ordering::Greater, Ordering::Equal) => Relation::Finishes,
+            (Ordering::Less, Ordering::Equal) => Relation::FinishedBy,
+            (Ordering::Greater, Ordering::Less) => Relation::During,
+            (Ordering::Less, Ordering::Greater) => Relation::Contains,
+            (Ordering::Less, Ordering::Less) => Relation::Overlaps,
+            (Ordering::Greater, Ordering::Greater) => Relation::OverlappedBy,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Relation::ALL
+            .iter()
+            .position(|r| r == self)
+            .expect("every relation is listed")
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Relation::Before => "before",
+            Relation::Meets => "meets",
+            Relation::Overlaps => "overlaps",
+            Relation::Starts => "starts",
+            Relation::During => "during",
+            Relation::Finishes => "finishes",
+            Relation::Equals => "equals",
+            Relation::FinishedBy => "finished by",
+            Relation::Contains => "contains",
+            Relation::StartedBy => "started by",
+            Relation::OverlappedBy => "overlapped by",
+            Relation::MetBy => "met by",
+            Relation::After => "after",
+        }
+    }
+
+    // One of the ranges holds every section of the other
+    pub fn is_containment(&self) -> bool {
+        matches!(
+            self,
+            Relation::Starts
+                | Relation::During
+                | Relation::Finishes
+                | Relation::Equals
+                | Relation::FinishedBy
+                | Relation::Contains
+                | Relation::StartedBy
+        )
+    }
+
+    // The left range lies within the right one
+    pub fn is_inside(&self) -> bool {
+        matches!(
+            self,
+            Relation::Starts | Relation::During | Relation::Finishes | Relation::Equals
+        )
+    }
+
+    // The right range lies within the left one
+    pub fn is_containing(&self) -> bool {
+        matches!(
+            self,
+            Relation::FinishedBy | Relation::Contains | Relation::StartedBy | Relation::Equals
+        )
+    }
+
+    // The ranges share at least one section
+    pub fn is_overlap(&self) -> bool {
+        !matches!(
+            self,
+            Relation::Before | Relation::Meets | Relation::MetBy | Relation::After
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::relation::*;
+
+    fn range(start: i64, end: i64) -> Range {
+        Range::inclusive(start, end).unwrap()
+    }
+
+    #[test]
+    fn classifies_all_relations() {
+        let base = range(3, 6);
+        let cases = [
+            (range(0, 1), Relation::Before),
+            (range(0, 2), Relation::Meets),
+            (range(1, 4), Relation::Overlaps),
+            (range(3, 4), Relation::Starts),
+            (range(4, 5), Relation::During),
+            (range(5, 6), Relation::Finishes),
+            (range(3, 6), Relation::Equals),
+            (range(2, 6), Relation::FinishedBy),
+            (range(2, 7), Relation::Contains),
+            (range(3, 8), Relation::StartedBy),
+            (range(5, 8), Relation::OverlappedBy),
+            (range(7, 9), Relation::MetBy),
+            (range(8, 9), Relation::After),
+        ];
+
+        for (other, relation) in cases {
+            assert_eq!(Relation::classify(&other, &base), relation, "{other:?}");
+        }
+    }
+
+    #[test]
+    fn matches_interval_operations() {
+        let ranges: Vec<Range> = (0..6)
+            .flat_map(|start| (start..6).map(move |end| range(start, end)))
+            .collect();
+
+        for a in ranges.iter() {
+            for b in ranges.iter() {
+                let relation = Relation::classify(a, b);
+
+                // The list is symmetric around equals
+                let inverse = Relation::ALL[12 - relation.index()];
+
+                assert_eq!(Relation::classify(b, a), inverse);
+                assert_eq!(relation.is_overlap(), a.overlaps(b));
+                assert_eq!(relation.is_containment(), a.covers(b) || b.covers(a));
+                assert_eq!(relation.is_inside(), b.covers(a));
+                assert_eq!(relation.is_containing(), a.covers(b));
+            }
+        }
+    }
+}
@@ -1,22 +1,9 @@
-fn char_to_index(c: &char) -> u8 {
-    match c {
-        'A'..='Z' => *c as u8 - 'A' as u8 + 1,
-        'a'..='z' => *c as u8 - 'a' as u8 + 27,
-        _ => 0,
-    }
-}
+use aoc::BitSet;
 
+// Every character is stored by its code point, so any of them can be compared
 fn is_all_unique(chars: &str) -> bool {
-    let mut cmp: u64 = 0;
-    for c in chars.chars() {
-        if cmp & (1 << char_to_index(&c)) != 0 {
-            return false;
-        }
-
-        cmp |= 1 << char_to_index(&c);
-    }
-
-    true
+    let mut seen = BitSet::new();
+    chars.chars().all(|c| seen.insert(c as usize))
 }
 
 fn find_first_n_unique(input: &str, n_unique: usize) -> Option<usize> {
@@ -48,7 +35,7 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::find_first_n_unique;
+    use crate::{find_first_n_unique, is_all_unique};
 
     #[test]
     fn ex_inputs_4_unique() {
@@ -67,4 +54,15 @@ mod tests {
         assert_eq!(find_first_n_unique("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14),Some(29));
         assert_eq!(find_first_n_unique("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14),Some(26));
     }
+
+    #[test]
+    fn other_characters() {
+        assert!(is_all_unique("ab1-"));
+        assert!(is_all_unique("a\nb\r"));
+        assert!(!is_all_unique("a1b1"));
+        assert!(!is_all_unique("--"));
+        assert!(is_all_unique("aé€"));
+        assert!(!is_all_unique("é1é"));
+        assert_eq!(find_first_n_unique("aa11a2b", 4), Some(7));
+    }
 }